@@ -1,27 +1,124 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::fs;
 use std::path;
 
 const MAX_ELVES: usize = 3;
 
+/// Groups of consecutive non-blank lines, separated by one or more blank lines. The last group
+/// does not need a trailing blank line.
+struct Paragraphs<'a> {
+    lines: std::str::Lines<'a>,
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut paragraph = Vec::new();
+        for line in self.lines.by_ref() {
+            if !line.trim().is_empty() {
+                paragraph.push(line);
+            } else if !paragraph.is_empty() {
+                break;
+            }
+        }
+        (!paragraph.is_empty()).then_some(paragraph)
+    }
+}
+
+fn paragraphs(input: &str) -> Paragraphs<'_> {
+    Paragraphs {
+        lines: input.lines(),
+    }
+}
+
+/// Keeps the `k` largest items pushed so far, using `O(k)` memory.
+struct TopK<T: Ord> {
+    k: usize,
+    // Min-heap, so the smallest kept item is the one to evict.
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if self
+            .heap
+            .peek()
+            .is_some_and(|Reverse(smallest)| item > *smallest)
+        {
+            self.heap.pop();
+            self.heap.push(Reverse(item));
+        }
+    }
+
+    /// The kept items, largest first.
+    fn into_sorted_vec(self) -> Vec<T> {
+        // Sorting `Reverse` ascending puts the largest item first.
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+/// The calories carried by the best-stocked elf, and by the top `MAX_ELVES` elves together.
+fn solve(contents: &str) -> (i32, i32) {
+    let mut top = TopK::new(MAX_ELVES);
+    top.extend(paragraphs(contents).map(|elf| {
+        elf.iter()
+            .map(|line| line.trim().parse::<i32>().expect("Invalid calories."))
+            .sum::<i32>()
+    }));
+    let top = top.into_sorted_vec();
+    (top.first().copied().unwrap_or(0), top.iter().sum())
+}
+
 fn main() {
     let file_path = path::Path::new("data/input.txt");
     let contents = fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    let mut max: [i32; MAX_ELVES] = [0; MAX_ELVES];
-    let mut sum = 0;
+    let (max, top_sum) = solve(&contents);
+    println!("Mode: Real");
+    println!("Part 1 - Solution: {}", max);
+    println!("Part 2 - Solution: {}", top_sum);
+}
 
-    for line in contents.lines() {
-        if line == "" {
-            if sum > max[0] {
-                max[0] = sum;
-                max.sort()
-            }
-            sum = 0;
-        } else {
-            let calories: i32 = line.parse().unwrap();
-            sum += calories;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_trailing_newline() {
+        // The last elf carries the most, and is not followed by a blank line.
+        let input = "1000\n2000\n\n4000\n\n\n5000\n6000\n\n7000\n8000\n9000";
+        assert_eq!((24000, 24000 + 11000 + 4000), solve(input));
+        assert_eq!(4, paragraphs(input).count());
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut top = TopK::new(2);
+        top.extend([3, 1, 4, 1, 5, 9, 2, 6]);
+        assert_eq!(vec![9, 6], top.into_sorted_vec());
+        assert!(TopK::<i32>::new(0).into_sorted_vec().is_empty());
     }
-    let result: i32 = max.iter().sum();
-    println!("Max: {}", result);
 }
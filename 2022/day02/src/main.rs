@@ -1,71 +1,223 @@
-use std::collections::HashMap;
 use std::fs;
 use std::path;
 
-const PART: u8 = 2;
+// Report which interpretation of the second column scores best.
+const OPTIMIZE: bool = false;
 
 fn read_file(file_path_as_str: &str) -> String {
     let file_path = path::Path::new(file_path_as_str);
-    let contents = fs::read_to_string(file_path).expect("Should have been able to read the file.");
+    fs::read_to_string(file_path).expect("Should have been able to read the file.")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Shape {
+    const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    fn score(&self) -> u32 {
+        match self {
+            Shape::Rock => 1,
+            Shape::Paper => 2,
+            Shape::Scissors => 3,
+        }
+    }
+
+    /// The shape this shape defeats.
+    fn beats(&self) -> Shape {
+        match self {
+            Shape::Rock => Shape::Scissors,
+            Shape::Paper => Shape::Rock,
+            Shape::Scissors => Shape::Paper,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+    fn score(&self) -> u32 {
+        match self {
+            Outcome::Lose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+
+    /// The outcome of playing `own` against `opponent`.
+    fn of(own: Shape, opponent: Shape) -> Outcome {
+        if own == opponent {
+            Outcome::Draw
+        } else if own.beats() == opponent {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    /// The shape to play against `opponent` to reach this outcome.
+    fn requires(&self, opponent: Shape) -> Shape {
+        Shape::ALL
+            .into_iter()
+            .find(|&own| Outcome::of(own, opponent) == *self)
+            .expect("Every outcome is reachable with some shape.")
+    }
+}
+
+fn round_score(own: Shape, opponent: Shape) -> u32 {
+    own.score() + Outcome::of(own, opponent).score()
+}
+
+/// How to read X, Y and Z in the second column of the strategy guide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Interpretation {
+    // X, Y and Z are the shapes to play.
+    Shapes([Shape; 3]),
+    // X, Y and Z are the outcomes to reach.
+    Outcomes([Outcome; 3]),
+}
+
+impl Interpretation {
+    const PART_1: Interpretation =
+        Interpretation::Shapes([Shape::Rock, Shape::Paper, Shape::Scissors]);
+    const PART_2: Interpretation =
+        Interpretation::Outcomes([Outcome::Lose, Outcome::Draw, Outcome::Win]);
+
+    /// Every assignment of X, Y and Z to distinct shapes or to distinct outcomes.
+    fn all() -> Vec<Interpretation> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let shapes = PERMUTATIONS
+            .iter()
+            .map(|p| Interpretation::Shapes(p.map(|i| Shape::ALL[i])));
+        let outcomes = PERMUTATIONS
+            .iter()
+            .map(|p| Interpretation::Outcomes(p.map(|i| Outcome::ALL[i])));
+        shapes.chain(outcomes).collect()
+    }
+
+    fn own_shape(&self, opponent: Shape, response: Response) -> Shape {
+        let index = response as usize;
+        match self {
+            Interpretation::Shapes(shapes) => shapes[index],
+            Interpretation::Outcomes(outcomes) => outcomes[index].requires(opponent),
+        }
+    }
+}
+
+/// The second column of the strategy guide, whose meaning depends on the interpretation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Response {
+    X = 0,
+    Y = 1,
+    Z = 2,
+}
+
+fn parse_guide(contents: &str) -> Vec<(Shape, Response)> {
     contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (opponent, response) = line.split_once(' ').expect("Invalid line.");
+            let opponent = match opponent {
+                "A" => Shape::Rock,
+                "B" => Shape::Paper,
+                "C" => Shape::Scissors,
+                _ => panic!("Invalid opponent shape: {opponent}"),
+            };
+            let response = match response.trim() {
+                "X" => Response::X,
+                "Y" => Response::Y,
+                "Z" => Response::Z,
+                _ => panic!("Invalid response: {response}"),
+            };
+            (opponent, response)
+        })
+        .collect()
 }
 
-fn create_score_mapping_part_1() -> HashMap<String, u32> {
-    let mut scores = HashMap::new();
-
-    // Brute force:
-    //  A: Rock, B: Paper, C: Scissors.
-    //  Part 1 - X: Rock -> 1, Y: Paper -> 2, Z: Scissors -> 3.
-    scores.insert(String::from("A X"), 1 + 3);
-    scores.insert(String::from("A Y"), 2 + 6);
-    scores.insert(String::from("A Z"), 3 + 0);
-    scores.insert(String::from("B X"), 1 + 0);
-    scores.insert(String::from("B Y"), 2 + 3);
-    scores.insert(String::from("B Z"), 3 + 6);
-    scores.insert(String::from("C X"), 1 + 6);
-    scores.insert(String::from("C Y"), 2 + 0);
-    scores.insert(String::from("C Z"), 3 + 3);
-
-    scores
+fn total_score(guide: &[(Shape, Response)], interpretation: &Interpretation) -> u32 {
+    guide
+        .iter()
+        .map(|&(opponent, response)| {
+            round_score(interpretation.own_shape(opponent, response), opponent)
+        })
+        .sum()
 }
 
-fn create_score_mapping_part_2() -> HashMap<String, u32> {
-    let mut scores = HashMap::new();
-
-    // Brute force:
-    //  Part 2 - X: Lose -> 0, Y: Draw -> 3, Z: Win -> 6.
-    //  Rock: 1, Paper: 2, Scissors: 3.
-    scores.insert(String::from("A X"), 3 + 0); // Rock -> Lose: Scissors.
-    scores.insert(String::from("A Y"), 1 + 3); // Rock -> Draw: Rock.
-    scores.insert(String::from("A Z"), 2 + 6); // Rock -> Win: Paper.
-    scores.insert(String::from("B X"), 1 + 0); // Paper -> Lose: Rock.
-    scores.insert(String::from("B Y"), 2 + 3); // Paper -> Draw: Paper.
-    scores.insert(String::from("B Z"), 3 + 6); // Paper -> Win: Scissors.
-    scores.insert(String::from("C X"), 2 + 0); // Scissors -> Lose: Paper.
-    scores.insert(String::from("C Y"), 3 + 3); // Scissors -> Draw: Scissors.
-    scores.insert(String::from("C Z"), 1 + 6); // Scissors -> Win: Rock.
-
-    scores
+/// Find the interpretation that maximises the total score, preferring the first on ties.
+fn optimize(guide: &[(Shape, Response)]) -> (Interpretation, u32) {
+    Interpretation::all()
+        .into_iter()
+        .map(|interpretation| (interpretation, total_score(guide, &interpretation)))
+        // `max_by_key` returns the last maximum, so reverse to prefer the first.
+        .rev()
+        .max_by_key(|&(_, score)| score)
+        .expect("There is at least one interpretation.")
 }
 
 fn main() {
     // Read file.
     let contents = read_file("data/input.txt");
+    let guide = parse_guide(&contents);
+
+    println!("Mode: Real");
+
+    // Part 1.
+    let part_1_score = total_score(&guide, &Interpretation::PART_1);
+    println!("Part 1 - Solution: {}", part_1_score);
+
+    // Part 2.
+    let part_2_score = total_score(&guide, &Interpretation::PART_2);
+    println!("Part 2 - Solution: {}", part_2_score);
+
+    if OPTIMIZE {
+        let (interpretation, score) = optimize(&guide);
+        println!(
+            "Best interpretation: {:?} - Score: {}",
+            interpretation, score
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "A Y\nB X\nC Z\n";
 
-    // Create score mapping.
-    let scores: HashMap<String, u32>;
-    if PART == 1 {
-        scores = create_score_mapping_part_1();
-    } else if PART == 2 {
-        scores = create_score_mapping_part_2();
-    } else {
-        panic!("Invalid part!");
+    #[test]
+    fn test_total_score() {
+        let guide = parse_guide(TEST_INPUT);
+        assert_eq!(15, total_score(&guide, &Interpretation::PART_1));
+        assert_eq!(12, total_score(&guide, &Interpretation::PART_2));
     }
 
-    // Compute scores.
-    let mut sum: u32 = 0;
-    for line in contents.lines() {
-        sum += scores[line];
+    #[test]
+    fn test_optimize() {
+        let (interpretation, score) = optimize(&parse_guide(TEST_INPUT));
+        // Reading X, Y and Z as shapes that win every round.
+        assert_eq!(
+            Interpretation::Shapes([Shape::Scissors, Shape::Paper, Shape::Rock]),
+            interpretation
+        );
+        assert_eq!(3 * 6 + 3 + 2 + 1, score);
     }
-    println!("Total score: {}", sum);
 }
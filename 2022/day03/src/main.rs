@@ -1,81 +1,88 @@
 use itertools::Itertools;
-use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
-use std::str::Lines;
+use std::str::FromStr;
 
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
-    let contents: String =
-        fs::read_to_string(file_path).expect("Should have been able to read the file.");
-    contents
+    fs::read_to_string(file_path).expect("Should have been able to read the file.")
 }
 
-fn generate_alphabet_map() -> HashMap<char, usize> {
-    // Generate alphabet.
-    let mut alphabet: Vec<char> = ('a'..='z').into_iter().collect::<Vec<char>>();
-    let upper_case_alphabet: Vec<char> = ('A'..='Z').into_iter().collect::<Vec<char>>();
-    alphabet.extend(upper_case_alphabet);
+/// A set of characters as a 64-bit mask. Letters take bits `1..=52` in the order `a-z`, `A-Z`, so
+/// the bit index of a letter is its priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+struct CharSet(u64);
+
+impl CharSet {
+    const EMPTY: CharSet = CharSet(0);
 
-    // Map to position.
-    let mut alphabet_map: HashMap<char, usize> = HashMap::new();
-    for (i, char) in alphabet.iter().enumerate() {
-        alphabet_map.insert(*char, i + 1);
+    fn bit(character: char) -> Option<u32> {
+        match character {
+            'a'..='z' => Some(character as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(character as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
     }
 
-    alphabet_map
-}
+    fn insert(&mut self, character: char) -> Result<(), String> {
+        let bit =
+            CharSet::bit(character).ok_or_else(|| format!("Unsupported character: {character}"))?;
+        self.0 |= 1 << bit;
+        Ok(())
+    }
 
-fn compute_priorities_part_1(contents: &String, alphabet_map: &HashMap<char, usize>) -> usize {
-    // Keep track of priorities.
-    let mut priorities = 0;
-
-    // Parse line by line.
-    for line in contents.lines() {
-        // Split lines by half.
-        let half_length: usize = line.len() / 2;
-        let first_half: &str = &line[..half_length];
-        let second_half: &str = &line[half_length..];
-
-        // De-duplicate first half to avoid double counting priorities.
-        let mut unique_first_half: Vec<char> = first_half.chars().collect();
-        unique_first_half.sort();
-        unique_first_half.dedup();
-
-        // Add priority of line to total priorities.
-        for char in unique_first_half {
-            if second_half.contains(char) {
-                priorities += alphabet_map[&char];
-            }
-        }
+    fn intersection(&self, other: &CharSet) -> CharSet {
+        CharSet(self.0 & other.0)
     }
 
-    priorities
+    /// The sum of the priorities of all letters in the set.
+    fn priority_sum(&self) -> u32 {
+        (1..=52).filter(|bit| self.0 & 1 << bit != 0).sum()
+    }
 }
 
-fn compute_priorities_part_2(contents: &String, alphabet_map: &HashMap<char, usize>) -> usize {
-    // Keep track of priorities.
-    let mut priorities = 0;
-
-    // Parse three lines at a time.
-    let lines: Lines = contents.lines();
-    for (line1, line2, line3) in lines.tuples() {
-        // De-duplicate first string to avoid double counting priorities.
-        let mut first_string: Vec<char> = line1.chars().collect();
-        first_string.sort();
-        first_string.dedup();
-
-        // Add priority of line to total priorities.
-        for char in first_string {
-            if line2.contains(char) {
-                if line3.contains(char) {
-                    priorities += alphabet_map[&char];
-                }
-            }
+impl FromStr for CharSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = CharSet::EMPTY;
+        for character in s.chars() {
+            set.insert(character)?;
         }
+        Ok(set)
     }
+}
 
-    priorities
+fn parse_rucksack(items: &str) -> CharSet {
+    items.parse().expect("Invalid rucksack.")
+}
+
+fn compute_priorities_part_1(contents: &str) -> u32 {
+    // Split lines by half, and sum the priorities of the items in both halves. Sets count every
+    // item once, so shared items are never double counted.
+    contents
+        .lines()
+        .map(|line| {
+            let (first_half, second_half) = line.split_at(line.len() / 2);
+            parse_rucksack(first_half)
+                .intersection(&parse_rucksack(second_half))
+                .priority_sum()
+        })
+        .sum()
+}
+
+fn compute_priorities_part_2(contents: &str) -> u32 {
+    // Parse three lines at a time, and sum the priorities of the items all three share.
+    contents
+        .lines()
+        .tuples()
+        .map(|(line1, line2, line3)| {
+            parse_rucksack(line1)
+                .intersection(&parse_rucksack(line2))
+                .intersection(&parse_rucksack(line3))
+                .priority_sum()
+        })
+        .sum()
 }
 
 fn main() {
@@ -83,14 +90,27 @@ fn main() {
     // let contents: String = read_file("data/test.txt");
     let contents: String = read_file("data/input.txt");
 
-    // Generate alphabet map.
-    let alphabet_map = generate_alphabet_map();
+    println!("Mode: Real");
 
     // Part 1.
-    let part_1_priorities = compute_priorities_part_1(&contents, &alphabet_map);
+    let part_1_priorities = compute_priorities_part_1(&contents);
     println!("Part 1 - Priorities: {}", part_1_priorities);
 
     // Part 2.
-    let part_2_priorities = compute_priorities_part_2(&contents, &alphabet_map);
+    let part_2_priorities = compute_priorities_part_2(&contents);
     println!("Part 2 - Priorities: {}", part_2_priorities);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priorities() {
+        let contents = fs::read_to_string("data/test.txt").unwrap();
+        assert_eq!(157, compute_priorities_part_1(&contents));
+        assert_eq!(70, compute_priorities_part_2(&contents));
+        assert_eq!(27 + 1, parse_rucksack("aAAa").priority_sum());
+        assert!("a-b".parse::<CharSet>().is_err());
+    }
+}
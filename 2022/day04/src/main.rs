@@ -1,91 +1,82 @@
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
-    let contents: String =
-        fs::read_to_string(file_path).expect("Should have been able to read the file.");
-    contents
+    fs::read_to_string(file_path).expect("Should have been able to read the file.")
 }
 
+/// An inclusive range of section ids, `start..=end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct Section {
     start: u32,
     end: u32,
 }
 
-fn parse_line(line: &str) -> (Section, Section) {
-    // Split the line in pair sections.
-    let pairs: Vec<&str> = line.split(",").collect();
-    // Parse the start and end of both sections.
-    let first_pair: Vec<&str> = pairs[0].split("-").collect();
-    let second_pair: Vec<&str> = pairs[1].split("-").collect();
-
-    // Map first section data in Section struct.
-    let first_section = Section {
-        start: first_pair[0].parse().unwrap(),
-        end: first_pair[1].parse().unwrap(),
-    };
-    // Map second section data in Section struct.
-    let second_section = Section {
-        start: second_pair[0].parse().unwrap(),
-        end: second_pair[1].parse().unwrap(),
-    };
-
-    (first_section, second_section)
-}
+impl Section {
+    fn new(start: u32, end: u32) -> Result<Section, String> {
+        if start > end {
+            return Err(format!(
+                "Section should not end before it starts: {start}-{end}"
+            ));
+        }
+        Ok(Section { start, end })
+    }
 
-fn find_overlaps_part_1(contents: &String) -> usize {
-    // Keep track of overlaps.
-    let mut overlaps = 0;
+    /// Whether `other` lies entirely within this section.
+    fn contains(&self, other: &Section) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
 
-    // Parse line by line.
-    for line in contents.lines() {
-        let (first_section, second_section) = parse_line(line);
+    fn overlaps(&self, other: &Section) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
 
-        if first_section.start <= second_section.start && first_section.end >= second_section.end {
-            overlaps += 1
-        } else if second_section.start <= first_section.start
-            && second_section.end >= first_section.end
-        {
-            overlaps += 1
-        }
+impl FromStr for Section {
+    type Err = String;
+
+    /// Parses a section such as `2-4`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| format!("Missing dash in section: {s}"))?;
+        let parse = |bound: &str| {
+            bound
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid section bound: {bound}"))
+        };
+        Section::new(parse(start)?, parse(end)?)
     }
-    overlaps
 }
 
-fn find_overlaps_part_2(contents: &String) -> usize {
-    // Keep track of overlaps.
-    let mut overlaps = 0;
+fn parse_line(line: &str) -> Result<(Section, Section), String> {
+    // Split the line in pair sections.
+    let (first_section, second_section) = line
+        .split_once(',')
+        .ok_or_else(|| format!("Missing comma in pair: {line}"))?;
+    Ok((first_section.parse()?, second_section.parse()?))
+}
 
-    // Parse line by line.
-    for line in contents.lines() {
-        let (first_section, second_section) = parse_line(line);
+fn count_pairs(contents: &str, predicate: impl Fn(&Section, &Section) -> bool) -> usize {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_line(line).expect("Invalid pair."))
+        .filter(|(first_section, second_section)| predicate(first_section, second_section))
+        .count()
+}
 
-        // First start encompassed in Second: second_start <= first_start <= second_end.
-        if second_section.start <= first_section.start && first_section.start <= second_section.end
-        {
-            overlaps += 1
-        }
-        // First end encompassed in Second: second_start <= first_end <= second_end
-        else if second_section.start <= first_section.end
-            && first_section.end <= second_section.end
-        {
-            overlaps += 1
-        }
-        // Second start encompassed in First: first_start <= second_start <= first_end.
-        else if first_section.start <= second_section.start
-            && second_section.start <= first_section.end
-        {
-            overlaps += 1
-        }
-        // Second end encompassed in First: first_start <= second_end <= first_end.
-        else if first_section.start <= second_section.end
-            && second_section.end <= first_section.end
-        {
-            overlaps += 1
-        }
-    }
-    overlaps
+fn find_overlaps_part_1(contents: &str) -> usize {
+    count_pairs(contents, |first, second| {
+        first.contains(second) || second.contains(first)
+    })
+}
+
+fn find_overlaps_part_2(contents: &str) -> usize {
+    count_pairs(contents, Section::overlaps)
 }
 
 fn main() {
@@ -93,6 +84,8 @@ fn main() {
     // let contents: String = read_file("data/test.txt");
     let contents: String = read_file("data/input.txt");
 
+    println!("Mode: Real");
+
     // Part 1.
     let part_1_overlaps = find_overlaps_part_1(&contents);
     println!("Part 1 - Overlaps: {}", part_1_overlaps);
@@ -101,3 +94,45 @@ fn main() {
     let part_2_overlaps = find_overlaps_part_2(&contents);
     println!("Part 2 - Overlaps: {}", part_2_overlaps);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// The original check: some bound of either section lies within the other.
+    fn overlaps_by_cases(first: &Section, second: &Section) -> bool {
+        (second.start <= first.start && first.start <= second.end)
+            || (second.start <= first.end && first.end <= second.end)
+            || (first.start <= second.start && second.start <= first.end)
+            || (first.start <= second.end && second.end <= first.end)
+    }
+
+    fn section() -> impl Strategy<Value = Section> {
+        (0u32..100, 0u32..100).prop_map(|(a, b)| Section::new(a.min(b), a.max(b)).unwrap())
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let (first, second) = parse_line("2-4,6-8").unwrap();
+        assert_eq!(Section::new(2, 4).unwrap(), first);
+        assert_eq!(Section::new(6, 8).unwrap(), second);
+        assert!(parse_line("2-4").is_err());
+        assert!(parse_line("2-4,8").is_err());
+        assert!(parse_line("4-2,6-8").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn overlaps_matches_four_cases(first in section(), second in section()) {
+            prop_assert_eq!(overlaps_by_cases(&first, &second), first.overlaps(&second));
+        }
+
+        #[test]
+        fn containment_implies_overlap(first in section(), second in section()) {
+            if first.contains(&second) {
+                prop_assert!(first.overlaps(&second));
+            }
+        }
+    }
+}
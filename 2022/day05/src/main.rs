@@ -1,11 +1,12 @@
-use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
 // Choose the mode: Real or Test.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Real,
     Test,
 }
@@ -17,151 +18,295 @@ fn read_file(file_path_as_str: &str) -> String {
     contents
 }
 
-fn remove_whitespace(s: &str) -> String {
-    s.chars().filter(|c| !c.is_whitespace()).collect()
+/// The crane model decides whether crates are moved one at a time or all at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CrateMover {
+    // Moves crates one by one, reversing their order.
+    Model9000,
+    // Moves multiple crates at once, retaining their order.
+    Model9001,
 }
 
-fn parse_cargo_data(
-    contents: &String,
-    number_of_stacks: usize,
-    cargo_height: usize,
-) -> HashMap<usize, Vec<String>> {
-    // Select cargo data.
-    let cargo_data: Vec<&str> = contents.lines().take(cargo_height).collect();
-
-    // Set up data structure.
-    let mut cargo_map = HashMap::new();
-    for stack in 1..number_of_stacks + 1 {
-        cargo_map.insert(stack, vec![]);
-    }
-
-    // Parse cargo data layer by layer.
-    for layer in (0..cargo_height).rev() {
-        // Extract data.
-        let layer_data: &str = cargo_data[layer];
-        for stack in 1..number_of_stacks + 1 {
-            let parsed_crate: &str = &layer_data[4 * (stack - 1)..4 * (stack - 1) + 3];
-            let stack_in_map = cargo_map.get_mut(&stack).unwrap();
-            let trimmed_crate = remove_whitespace(parsed_crate);
-            if trimmed_crate != "" {
-                stack_in_map.push(trimmed_crate);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Move {
+    number_of_crates: usize,
+    // Stacks are numbered from 1, as in the drawing.
+    from_stack: usize,
+    to_stack: usize,
+}
+
+impl Move {
+    fn inverse(&self) -> Move {
+        Move {
+            number_of_crates: self.number_of_crates,
+            from_stack: self.to_stack,
+            to_stack: self.from_stack,
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        match parts[..] {
+            ["move", number_of_crates, "from", from_stack, "to", to_stack] => {
+                let parse = |value: &str| {
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid number in instruction: {s}"))
+                };
+                Ok(Move {
+                    number_of_crates: parse(number_of_crates)?,
+                    from_stack: parse(from_stack)?,
+                    to_stack: parse(to_stack)?,
+                })
             }
+            _ => Err(format!("Invalid instruction: {s}")),
         }
     }
+}
 
-    cargo_map
+#[derive(Clone, Debug)]
+struct Cargo {
+    // Bottom crate first.
+    stacks: Vec<Vec<char>>,
+    model: CrateMover,
+    // Applied moves, most recent last.
+    log: Vec<Move>,
 }
 
-fn parse_instruction(instruction: &str) -> (usize, usize, usize) {
-    let instruction_parts: Vec<&str> = instruction.split_whitespace().collect();
-    let number_of_crates: usize = instruction_parts[1].parse().unwrap();
-    let from_stack: usize = instruction_parts[3].parse().unwrap();
-    let to_stack: usize = instruction_parts[5].parse().unwrap();
+impl Cargo {
+    /// Parse the drawing of the stacks. The number of stacks and their horizontal positions are
+    /// taken from the numbered footer line, so the drawing can have any height or width and
+    /// lines may lack trailing whitespace.
+    fn parse(drawing: &str, model: CrateMover) -> Result<Cargo, String> {
+        let mut lines: Vec<&str> = drawing
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let footer = lines.pop().ok_or("Drawing is empty.")?;
 
-    (number_of_crates, from_stack, to_stack)
-}
+        // Find the character column of every stack label.
+        let mut columns: Vec<usize> = Vec::new();
+        let characters: Vec<char> = footer.chars().collect();
+        for (column, label) in characters.iter().enumerate() {
+            if label.is_whitespace() {
+                continue;
+            }
+            if !label.is_ascii_digit() {
+                return Err(format!("Invalid stack label in footer: {footer}"));
+            }
+            // Labels with several digits are right-aligned, so crates sit above the last digit.
+            if characters.get(column + 1).is_none_or(|c| c.is_whitespace()) {
+                columns.push(column);
+            }
+        }
 
-fn format_solution(cargo_map: &HashMap<usize, Vec<String>>, number_of_stacks: usize) -> String {
-    let mut solution: String = String::from("");
-    for stack in 1..number_of_stacks + 1 {
-        let stack_in_map = cargo_map.get(&stack).unwrap();
-        let top_crate = stack_in_map.last().unwrap();
-        solution = solution + &top_crate.replace("[", "").replace("]", "");
+        let mut stacks: Vec<Vec<char>> = vec![Vec::new(); columns.len()];
+        // Build the stacks bottom-up.
+        for line in lines.iter().rev() {
+            let characters: Vec<char> = line.chars().collect();
+            for (stack, &column) in stacks.iter_mut().zip(columns.iter()) {
+                match characters.get(column) {
+                    Some(&crate_) if crate_.is_alphabetic() => stack.push(crate_),
+                    Some(' ') | None => {}
+                    Some(_) => return Err(format!("Invalid crate in line: {line}")),
+                }
+            }
+        }
+
+        Ok(Cargo {
+            stacks,
+            model,
+            log: Vec::new(),
+        })
     }
 
-    solution
-}
+    fn stack_index(&self, stack: usize) -> Result<usize, String> {
+        if stack == 0 || stack > self.stacks.len() {
+            return Err(format!("Stack {stack} does not exist."));
+        }
+        Ok(stack - 1)
+    }
 
-fn solve_part_1(
-    contents: &String,
-    cargo_map: &mut HashMap<usize, Vec<String>>,
-    number_of_stacks: usize,
-    cargo_height: usize,
-) -> String {
-    // Select instruction data.
-    let instructions: Vec<&str> = contents.lines().skip(cargo_height + 2).collect();
-
-    // Parse instruction data line by line.
-    for instruction in instructions {
-        let (number_of_crates, from_stack, to_stack) = parse_instruction(instruction);
-
-        // Move crates around.
-        for _ in 0..number_of_crates {
-            let from_stack_in_map = cargo_map.get_mut(&from_stack).unwrap();
-            let crate_ = from_stack_in_map.pop().unwrap();
-
-            let to_stack_in_map = cargo_map.get_mut(&to_stack).unwrap();
-            to_stack_in_map.push(crate_);
+    fn transfer(&mut self, move_: &Move) -> Result<(), String> {
+        let from = self.stack_index(move_.from_stack)?;
+        let to = self.stack_index(move_.to_stack)?;
+        let from_height = self.stacks[from].len();
+        if move_.number_of_crates > from_height {
+            return Err(format!(
+                "Cannot move {} crates from stack {} holding {from_height}.",
+                move_.number_of_crates, move_.from_stack
+            ));
         }
+
+        let mut crates = self.stacks[from].split_off(from_height - move_.number_of_crates);
+        if self.model == CrateMover::Model9000 {
+            crates.reverse();
+        }
+        self.stacks[to].extend(crates);
+        Ok(())
     }
 
-    // Format solution.
-    format_solution(&cargo_map, number_of_stacks)
-}
+    fn apply(&mut self, move_: Move) -> Result<(), String> {
+        self.transfer(&move_)?;
+        self.log.push(move_);
+        Ok(())
+    }
+
+    /// Revert the most recent move. Moving the same crates back with the same crane restores
+    /// their original order for both models.
+    fn undo(&mut self) -> Option<Move> {
+        let move_ = self.log.pop()?;
+        self.transfer(&move_.inverse())
+            .expect("Should be able to revert an applied move.");
+        Some(move_)
+    }
+
+    /// Undo every logged move and apply them again with another crane.
+    fn replay_with(&mut self, model: CrateMover) -> Result<(), String> {
+        let moves = self.log.clone();
+        while self.undo().is_some() {}
+        self.model = model;
+        moves.into_iter().try_for_each(|move_| self.apply(move_))
+    }
 
-fn solve_part_2(
-    contents: &String,
-    cargo_map: &mut HashMap<usize, Vec<String>>,
-    number_of_stacks: usize,
-    cargo_height: usize,
-) -> String {
-    // Select instruction data.
-    let instructions: Vec<&str> = contents.lines().skip(cargo_height + 2).collect();
+    /// The top crate of every stack, in stack order.
+    fn top_crates(&self) -> Result<String, String> {
+        self.stacks
+            .iter()
+            .enumerate()
+            .map(|(i, stack)| {
+                stack
+                    .last()
+                    .copied()
+                    .ok_or_else(|| format!("Stack {} is empty.", i + 1))
+            })
+            .collect()
+    }
+}
 
-    // Parse instruction data line by line.
-    for instruction in instructions {
-        let (number_of_crates, from_stack, to_stack) = parse_instruction(instruction);
+fn rearrange(contents: &str, model: CrateMover) -> Result<Cargo, String> {
+    // The drawing and the instructions are separated by an empty line.
+    let contents = contents.replace("\r\n", "\n");
+    let (drawing, instructions) = contents
+        .split_once("\n\n")
+        .ok_or("Missing empty line between drawing and instructions.")?;
 
-        // Move crates around.
-        let from_stack_in_map = cargo_map.get_mut(&from_stack).unwrap();
-        let crates = from_stack_in_map.split_off(from_stack_in_map.len() - number_of_crates);
-        let to_stack_in_map = cargo_map.get_mut(&to_stack).unwrap();
-        to_stack_in_map.extend(crates);
+    let mut cargo = Cargo::parse(drawing, model)?;
+    for instruction in instructions.lines().filter(|line| !line.trim().is_empty()) {
+        cargo.apply(instruction.parse()?)?;
     }
+    Ok(cargo)
+}
 
-    // Format solution.
-    format_solution(&cargo_map, number_of_stacks)
+fn solve_part_1(contents: &str) -> String {
+    rearrange(contents, CrateMover::Model9000)
+        .and_then(|cargo| cargo.top_crates())
+        .expect("Should have been able to rearrange the crates.")
+}
+
+fn solve_part_2(contents: &str) -> String {
+    // The same moves as in part 1, replayed with the newer crane.
+    rearrange(contents, CrateMover::Model9000)
+        .and_then(|mut cargo| {
+            cargo.replay_with(CrateMover::Model9001)?;
+            cargo.top_crates()
+        })
+        .expect("Should have been able to rearrange the crates.")
 }
 
 fn main() {
     // Initialize problem.
-    let contents: String;
-    let number_of_stacks: usize;
-    let cargo_height: usize;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
-            number_of_stacks = 3;
-            cargo_height = 3;
-        }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
-            number_of_stacks = 9;
-            cargo_height = 8;
-        }
-    }
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
 
-    // Parse cargo data.
-    let cargo_map: HashMap<usize, Vec<String>> =
-        parse_cargo_data(&contents, number_of_stacks, cargo_height);
+    println!("Mode: {:?}", CHOSEN_MODE);
 
     // Part 1.
-    let part_1_solution = solve_part_1(
-        &contents,
-        &mut cargo_map.clone(),
-        number_of_stacks,
-        cargo_height,
-    );
+    let part_1_solution = solve_part_1(&contents);
     println!("Part 1 - Solution: {}", part_1_solution);
 
     // Part 2.
-    let part_2_solution = solve_part_2(
-        &contents,
-        &mut cargo_map.clone(),
-        number_of_stacks,
-        cargo_height,
-    );
+    let part_2_solution = solve_part_2(&contents);
     println!("Part 2 - Solution: {}", part_2_solution);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trailing whitespace is stripped on purpose, to check ragged lines parse.
+    const TEST_INPUT: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn test_parse_infers_stacks() {
+        let cargo = Cargo::parse(
+            "[A]                                 [J]\n 1   2   3   4   5   6   7   8   9  10",
+            CrateMover::Model9000,
+        )
+        .unwrap();
+        assert_eq!(10, cargo.stacks.len());
+        assert_eq!(vec!['A'], cargo.stacks[0]);
+        assert_eq!(vec!['J'], cargo.stacks[9]);
+        assert!(cargo.stacks[1..9].iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_top_crates_of_empty_stack() {
+        let cargo = Cargo::parse("[A]\n 1   2", CrateMover::Model9000).unwrap();
+        assert_eq!(Err("Stack 2 is empty.".to_string()), cargo.top_crates());
+    }
+
+    #[test]
+    fn test_rearrange() {
+        assert_eq!("CMZ", solve_part_1(TEST_INPUT));
+        assert_eq!("MCD", solve_part_2(TEST_INPUT));
+    }
+
+    #[test]
+    fn test_rearrange_crlf() {
+        let contents = TEST_INPUT.replace('\n', "\r\n");
+        assert_eq!("CMZ", solve_part_1(&contents));
+        assert_eq!("MCD", solve_part_2(&contents));
+    }
+
+    #[test]
+    fn test_replay_with() {
+        let mut cargo = rearrange(TEST_INPUT, CrateMover::Model9000).unwrap();
+        cargo.replay_with(CrateMover::Model9001).unwrap();
+        assert_eq!(4, cargo.log.len());
+        assert_eq!(
+            rearrange(TEST_INPUT, CrateMover::Model9001).unwrap().stacks,
+            cargo.stacks
+        );
+    }
+
+    #[test]
+    fn test_undo() {
+        for model in [CrateMover::Model9000, CrateMover::Model9001] {
+            let mut cargo = rearrange(TEST_INPUT, model).unwrap();
+            while cargo.undo().is_some() {}
+            assert_eq!(Ok("NDP".to_string()), cargo.top_crates());
+            assert_eq!(
+                vec!['Z', 'M', 'P'],
+                cargo.stacks.iter().map(|s| s[0]).collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -1,67 +1,175 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fs;
+use std::hash::Hash;
 use std::path::Path;
 
 // Choose the mode: Real or Test.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
-    let contents: String =
-        fs::read_to_string(file_path).expect("Should have been able to read the file.");
-    contents
+    fs::read_to_string(file_path).expect("Should have been able to read the file.")
 }
 
-fn solve_puzzle(contents: &String, number_of_unique_characters: usize) {
-    // Parse line by line.
-    for line in contents.lines() {
-        // Keep track of last n characters.
-        let mut last_n_characters: Vec<char> = vec!['a'; number_of_unique_characters];
-        // Loop over characters in input line.
-        for (index, char) in line.chars().enumerate() {
-            // First populate the vector.
-            if index < number_of_unique_characters {
-                last_n_characters[index] = char;
-            } else {
-                // Create a set to find the unique characters in the vector.
-                let last_unique_n_characters: HashSet<char> =
-                    HashSet::from_iter(last_n_characters.iter().cloned());
-                // If all last n are unique, then print the solution.
-                if last_n_characters.len() == last_unique_n_characters.len() {
-                    println!("Solution (with {}): {}", number_of_unique_characters, index);
-                    break;
-                // Else replace the relevant item in the last n character vector (cycling around).
-                } else {
-                    let index_to_update = index %  number_of_unique_characters;
-                    last_n_characters[index_to_update] = char;
+/// A set of characters as a 64-bit mask over `a-z` and `A-Z`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+struct CharSet(u64);
+
+impl CharSet {
+    const EMPTY: CharSet = CharSet(0);
+    // Number of distinct characters a set can hold.
+    const CAPACITY: usize = 52;
+
+    fn bit(character: char) -> Option<u32> {
+        match character {
+            'a'..='z' => Some(character as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(character as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+
+    /// Adds the character if it is absent and removes it if it is present.
+    fn toggle(&mut self, character: char) -> Result<(), String> {
+        let bit =
+            CharSet::bit(character).ok_or_else(|| format!("Unsupported character: {character}"))?;
+        self.0 ^= 1 << bit;
+        Ok(())
+    }
+
+    fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+/// Like `first_distinct_window`, but in constant time per character using a `CharSet`. Fails for
+/// characters the set cannot hold and windows larger than `CharSet::CAPACITY`.
+fn first_distinct_chars(characters: &[char], n: usize) -> Result<Option<usize>, String> {
+    if n > CharSet::CAPACITY {
+        return Err(format!("Window too large for a character set: {n}"));
+    }
+    if n == 0 {
+        return Ok(Some(0));
+    }
+    // Toggling every character entering and leaving the window keeps the characters that occur
+    // an odd number of times. Duplicates cancel out, so only a window of `n` distinct characters
+    // holds `n` of them.
+    let mut window = CharSet::EMPTY;
+    for (index, &character) in characters.iter().enumerate() {
+        window.toggle(character)?;
+        if index >= n {
+            window.toggle(characters[index - n])?;
+        }
+        if window.len() as usize == n {
+            return Ok(Some(index + 1));
+        }
+    }
+    Ok(None)
+}
+
+/// The number of items read up to and including the first `n` distinct items in a row.
+fn first_distinct_window<T: Eq + Hash>(items: &[T], n: usize) -> Option<usize> {
+    if n == 0 {
+        return Some(0);
+    }
+    // How often every item occurs in the window; the window is distinct once it holds `n` keys.
+    let mut counts: HashMap<&T, usize> = HashMap::new();
+    for (index, item) in items.iter().enumerate() {
+        *counts.entry(item).or_insert(0) += 1;
+        if index >= n {
+            let leaving = &items[index - n];
+            if let Some(count) = counts.get_mut(leaving) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(leaving);
                 }
             }
         }
+        if counts.len() == n {
+            return Some(index + 1);
+        }
     }
+    None
+}
+
+fn find_marker(datastream: &str, n: usize) -> Option<usize> {
+    let characters: Vec<char> = datastream.chars().collect();
+    // Fall back to hashing for anything the bitset cannot hold.
+    first_distinct_chars(&characters, n).unwrap_or_else(|_| first_distinct_window(&characters, n))
+}
+
+/// The marker position for every datastream in the input, one per non-empty line.
+fn solve_puzzle(contents: &str, number_of_unique_characters: usize) -> Vec<Option<usize>> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| find_marker(line, number_of_unique_characters))
+        .collect()
+}
+
+fn format_markers(markers: &[Option<usize>]) -> String {
+    markers
+        .iter()
+        .map(|marker| match marker {
+            Some(position) => position.to_string(),
+            None => "no marker".to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
 fn main() {
     // Initialize problem.
-    let contents: String;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
-        }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
-        }
-    }
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
+
+    println!("Mode: {:?}", CHOSEN_MODE);
 
     // Part 1.
-    solve_puzzle(&contents, 4);
+    let start_of_packet = solve_puzzle(&contents, 4);
+    println!("Part 1 - Solution: {}", format_markers(&start_of_packet));
 
     // Part 2.
-    solve_puzzle(&contents, 14);
+    let start_of_message = solve_puzzle(&contents, 14);
+    println!("Part 2 - Solution: {}", format_markers(&start_of_message));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_marker() {
+        let contents = fs::read_to_string("data/test.txt").unwrap();
+        assert_eq!(
+            vec![Some(7), Some(5), Some(6), Some(10), Some(11)],
+            solve_puzzle(&contents, 4)
+        );
+        assert_eq!(
+            vec![Some(19), Some(23), Some(23), Some(29), Some(26)],
+            solve_puzzle(&contents, 14)
+        );
+        // Three of a kind leave one copy in the window, which must still not count as distinct.
+        assert_eq!(Some(6), find_marker("aaabcd", 4));
+        assert_eq!(None, find_marker("abcabc", 4));
+    }
+
+    #[test]
+    fn test_marker_in_first_window() {
+        // A marker made of the first characters must be found, whatever they are.
+        assert_eq!(Some(4), find_marker("abcd", 4));
+        assert_eq!(Some(4), find_marker("a1b2", 4));
+        assert_eq!(Some(3), first_distinct_window(&[7, 8, 9, 7], 3));
+        assert_eq!(None, first_distinct_window(&[7, 7, 7], 2));
+    }
 }
@@ -4,9 +4,10 @@ use std::path::Path;
 use std::path::PathBuf;
 
 // Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
@@ -18,12 +19,12 @@ const UPDATE_SIZE: u32 = 30000000; // Free space required for update.
 
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
-    let contents: String =
-        fs::read_to_string(file_path).expect("Should have been able to read the file.");
-    contents
+    fs::read_to_string(file_path).expect("Should have been able to read the file.")
 }
 
-fn solve_puzzle(contents: &String) {
+/// The total size of all directories up to the cutoff, and the size of the smallest directory
+/// that frees up enough space for the update.
+fn solve_puzzle(contents: &str) -> (u32, u32) {
     let mut files: HashMap<PathBuf, u32> = HashMap::new();
     let mut current_path: PathBuf = PathBuf::new();
     // Parse line by line.
@@ -72,7 +73,6 @@ fn solve_puzzle(contents: &String) {
             size_counter += directory_size
         }
     }
-    println!("Part 1 - Solution: {}", size_counter);
 
     // Part 2 - Find best (minimal delete that allows for update) directory.
     let total_used_space: &u32 = directories.get(&root_path).unwrap();
@@ -82,28 +82,27 @@ fn solve_puzzle(contents: &String) {
     //
     for directory_size in directories.values() {
         if directory_size >= &additional_space_required_for_update
-            && directory_size < &current_smallest_size
+            && directory_size < current_smallest_size
         {
             current_smallest_size = directory_size;
         }
     }
-    println!("Part 2 - Solution: {}", current_smallest_size);
+    (size_counter, *current_smallest_size)
 }
 
 fn main() {
     // Initialize problem.
-    let contents: String;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
-        }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
-        }
-    }
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
+
+    println!("Mode: {:?}", CHOSEN_MODE);
 
     // Solve part 1 and 2.
-    solve_puzzle(&contents);
+    let (part_1_size, part_2_size) = solve_puzzle(&contents);
+    println!("Part 1 - Solution: {}", part_1_size);
+    println!("Part 2 - Solution: {}", part_2_size);
 }
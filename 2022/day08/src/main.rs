@@ -1,15 +1,18 @@
-use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
 // Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
+// Print a heat map of the scenic scores after solving.
+const HEAT_MAP: bool = false;
+
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
     let contents: String =
@@ -17,190 +20,222 @@ fn read_file(file_path_as_str: &str) -> String {
     contents
 }
 
-fn parse_data(contents: &String) -> Vec<Vec<u8>> {
-    let mut rows: Vec<Vec<u8>> = Vec::new();
-    // Parse line by line.
-    for line in contents.lines() {
-        let heights: Vec<u8> = line
-            .split("")
-            .filter(|&x| !x.is_empty())
-            .map(|x| x.parse().unwrap())
-            .collect();
-        rows.push(heights);
+/// A rectangular grid stored row-major in a single vector, indexed by `(row, column)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "Grid dimensions do not match cells."
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Parses one row per line, converting every character with `f`.
+    fn parse(input: &str, f: impl Fn(char) -> T) -> Self {
+        let mut width = 0;
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let row_start = cells.len();
+            cells.extend(line.chars().map(&f));
+            width = cells.len() - row_start;
+            height += 1;
+        }
+        Grid::new(width, height, cells)
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
     }
-    rows
 }
 
-fn transpose<T>(v: &Vec<Vec<T>>) -> Vec<Vec<T>>
-where
-    T: Clone,
-{
-    assert!(!v.is_empty());
-    (0..v[0].len())
-        .map(|i| v.iter().map(|inner| inner[i].clone()).collect::<Vec<T>>())
-        .collect()
+impl<T> std::ops::Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        assert!(
+            row < self.height && column < self.width,
+            "Position out of bounds."
+        );
+        &self.cells[row * self.width + column]
+    }
 }
 
-fn add_number_of_visible_trees_for_row_direction(
-    data: &Vec<Vec<u8>>,
-    reverse: bool,
-) -> HashSet<(usize, usize, u8)> {
-    let mut counted_trees: HashSet<(usize, usize, u8)> = HashSet::new();
-    let max_dim_1 = data.len();
-    let max_dim_2 = data[0].len();
-
-    for row in 1..max_dim_1 - 1 {
-        let start_tree_height: u8 = if reverse {
-            data[row][data[0].len() - 1]
-        } else {
-            data[row][0]
-        };
-        let mut highest_tree_so_far: u8 = start_tree_height;
-
-        // NOTE: More performant (without allocating) option with itertools::Either exists.
-        let range_: Vec<usize> = if reverse {
-            (1..max_dim_2 - 1).rev().collect()
-        } else {
-            (1..max_dim_2 - 1).collect()
-        };
-        for tree in range_ {
-            let tree_height: u8 = data[row][tree];
-            if tree_height > highest_tree_so_far {
-                highest_tree_so_far = tree_height;
-                counted_trees.insert((row, tree, tree_height));
-            }
-        }
+impl<T> std::ops::IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.height && column < self.width,
+            "Position out of bounds."
+        );
+        &mut self.cells[row * self.width + column]
     }
+}
 
-    counted_trees
+fn parse_data(contents: &str) -> Grid<u8> {
+    Grid::parse(contents, |c| {
+        c.to_digit(10).expect("Tree height should be a digit.") as u8
+    })
 }
 
-fn solve_part_1(data: &Vec<Vec<u8>>) -> u32 {
-    let forest_len_y: u32 = data.len() as u32;
-    let forest_len_x: u32 = data[0].len() as u32;
-    let mut counted_trees: HashSet<(usize, usize, u8)> = HashSet::new();
-
-    // Keep track of solution.
-    // Initialize to outer trees. NOTE: Avoid double counting the edges.
-    let mut solution: u32 = forest_len_x * 2 + (forest_len_y - 2) * 2;
-
-    // Parse row by row going from the left.
-    let additional_trees = add_number_of_visible_trees_for_row_direction(data, false);
-    counted_trees.extend(additional_trees);
-
-    // Parse row by row going from the right.
-    let additional_trees = add_number_of_visible_trees_for_row_direction(data, true);
-    counted_trees.extend(additional_trees);
-
-    // Transpose the input data.
-    let transposed_data: Vec<Vec<u8>> = transpose(data);
-
-    // Parse column by column going from the top.
-    let additional_trees = add_number_of_visible_trees_for_row_direction(&transposed_data, false);
-    // Swap coordinates again given transpose.
-    counted_trees.extend(
-        additional_trees
-            .iter()
-            .map(|tree: &(usize, usize, u8)| (tree.1, tree.0, tree.2)),
-    );
-
-    // Parse column by column going from the bottom.
-    let additional_trees = add_number_of_visible_trees_for_row_direction(&transposed_data, true);
-    // Swap coordinates again given transpose.
-    counted_trees.extend(
-        additional_trees
-            .iter()
-            .map(|tree: &(usize, usize, u8)| (tree.1, tree.0, tree.2)),
-    );
-
-    // Add unique visible trees in the center.
-    solution += counted_trees.len() as u32;
-
-    solution
+struct Survey {
+    visible: Grid<bool>,
+    scenic_scores: Grid<u32>,
 }
 
-fn compute_viewing_distance(row: &Vec<u8>, current_index: usize, increase: bool) -> u32 {
-    let mut viewing_distance: u32 = 0;
-    let tree_height: u8 = row[current_index];
-    let mut loop_index: usize = current_index;
-
-    let end_index = if increase { row.len() - 1 } else { usize::MIN };
-    while if increase {
-        end_index > loop_index
-    } else {
-        loop_index > end_index
-    } {
-        if increase {
-            loop_index += 1
-        } else {
-            loop_index -= 1
-        };
-        if tree_height > row[loop_index] {
-            viewing_distance += 1;
-        } else if tree_height <= row[loop_index] {
-            viewing_distance += 1;
-            break;
+/// Walk a single line of sight, keeping a monotonic stack of trees that can still block the view
+/// of trees further along. Each tree is pushed and popped at most once, so a sweep is linear.
+fn sweep(forest: &Grid<u8>, line: impl Iterator<Item = (usize, usize)>, survey: &mut Survey) {
+    // Holds (step along the line, height) with non-increasing heights from bottom to top.
+    let mut stack: Vec<(u32, u8)> = Vec::new();
+    for (step, position) in line.enumerate() {
+        let step = step as u32;
+        let height = forest[position];
+        // Lower trees are hidden behind this one for everything that follows.
+        while stack.last().is_some_and(|&(_, other)| other < height) {
+            stack.pop();
+        }
+        match stack.last() {
+            // Blocked by the nearest tree at least as high.
+            Some(&(blocking_step, _)) => survey.scenic_scores[position] *= step - blocking_step,
+            // Nothing blocks the view, so the tree is visible and sees the edge.
+            None => {
+                survey.visible[position] = true;
+                survey.scenic_scores[position] *= step;
+            }
         }
+        stack.push((step, height));
+    }
+}
+
+fn survey_forest(forest: &Grid<u8>) -> Survey {
+    let (width, height) = (forest.width, forest.height);
+    let mut survey = Survey {
+        visible: Grid::filled(width, height, false),
+        scenic_scores: Grid::filled(width, height, 1),
+    };
+
+    for row in 0..height {
+        // Looking from the left and from the right.
+        sweep(forest, (0..width).map(|column| (row, column)), &mut survey);
+        sweep(
+            forest,
+            (0..width).rev().map(|column| (row, column)),
+            &mut survey,
+        );
+    }
+    for column in 0..width {
+        // Looking from the top and from the bottom.
+        sweep(forest, (0..height).map(|row| (row, column)), &mut survey);
+        sweep(
+            forest,
+            (0..height).rev().map(|row| (row, column)),
+            &mut survey,
+        );
     }
 
-    viewing_distance
+    survey
 }
 
-fn solve_part_2(data: &Vec<Vec<u8>>) -> u32 {
-    // Keep track of solution.
-    let mut solution = 0;
-
-    // Transpose the input data.
-    let transposed_data: Vec<Vec<u8>> = transpose(data);
-
-    // Loop over the rows.
-    for row_index in 0..data.len() {
-        for column_index in 0..data[0].len() {
-            let left_viewing_distance =
-                compute_viewing_distance(&data[row_index], column_index, false);
-            let right_viewing_distance =
-                compute_viewing_distance(&data[row_index], column_index, true);
-            let top_viewing_distance =
-                compute_viewing_distance(&transposed_data[column_index], row_index, false);
-            let bottom_viewing_distance =
-                compute_viewing_distance(&transposed_data[column_index], row_index, true);
-
-            // Compute scenic score.
-            let current_scenic_score: u32 = left_viewing_distance
-                * right_viewing_distance
-                * top_viewing_distance
-                * bottom_viewing_distance;
-
-            // Update solution when a higher scenic score is found.
-            if current_scenic_score > solution {
-                solution = current_scenic_score;
-            }
+/// Render scenic scores as characters of increasing density, scaled to the highest score.
+fn render_heat_map(scenic_scores: &Grid<u32>) -> String {
+    const SHADES: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+    let max_score = scenic_scores
+        .cells
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1) as u64;
+
+    let mut heat_map = String::new();
+    for row in scenic_scores.rows() {
+        for &score in row {
+            let shade = score as u64 * (SHADES.len() as u64 - 1) / max_score;
+            heat_map.push(SHADES[shade as usize]);
         }
+        heat_map.push('\n');
+    }
+    heat_map
+}
+
+fn solve(contents: &str) -> (usize, u32) {
+    let survey = survey_forest(&parse_data(contents));
+    if HEAT_MAP {
+        print!("{}", render_heat_map(&survey.scenic_scores));
     }
 
-    solution
+    let visible_trees = survey
+        .visible
+        .cells
+        .iter()
+        .filter(|&&visible| visible)
+        .count();
+    let highest_scenic_score = survey
+        .scenic_scores
+        .cells
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    (visible_trees, highest_scenic_score)
 }
 
 fn main() {
     // Initialize problem.
-    let contents: String;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
-        }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
-        }
-    }
-    let data: Vec<Vec<u8>> = parse_data(&contents);
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
+
+    let (part_1_score, part_2_score) = solve(&contents);
+
+    println!("Mode: {:?}", CHOSEN_MODE);
 
     // Part 1.
-    let part_1_score = solve_part_1(&data);
     println!("Part 1 - Solution: {}", part_1_score);
 
     // Part 2.
-    let part_2_score = solve_part_2(&data);
     println!("Part 2 - Solution: {}", part_2_score);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "30373\n25512\n65332\n33549\n35390\n";
+
+    #[test]
+    fn test_solve() {
+        assert_eq!((21, 8), solve(TEST_INPUT));
+    }
+
+    #[test]
+    fn test_scenic_scores() {
+        let survey = survey_forest(&parse_data(TEST_INPUT));
+        assert_eq!(4, survey.scenic_scores[(1, 2)]);
+        assert_eq!(8, survey.scenic_scores[(3, 2)]);
+        // Trees on the edge see nothing in at least one direction.
+        assert_eq!(0, survey.scenic_scores[(0, 3)]);
+    }
+}
@@ -1,55 +1,60 @@
-fn is_diagonal(position1: &(i32, i32), position2: &(i32, i32)) -> bool {
-    let x_distance: i32 = (position1.0 as i32 - position2.0 as i32).abs();
-    let y_distance: i32 = (position1.1 as i32 - position2.1 as i32).abs();
-    if x_distance > 1 || y_distance > 1 {
-        false
-    } else {
-        true
+use std::ops::{Add, Sub};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    /// Returns a point with each coordinate clamped to -1, 0 or 1.
+    pub fn signum(&self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
     }
 }
 
-pub fn is_adjacent(position1: &(i32, i32), position2: &(i32, i32)) -> bool {
-    // Same means adjacent.
-    if position1 == position2 {
-        true
-    } else if
-    // Same column, different row.
-    (position1.0 == position2.0 && (
-            position1.1 == position2.1 + 1 ||
-            position1.1 == position2.1 - 1
-        ))
-        ||
-        // Same row, different column.
-        (position1.1 == position2.1 && (
-            position1.0 == position2.0 + 1 ||
-            position1.0 == position2.0 - 1
-        ))
-    {
-        true
-    // Diagonal means adjacent.
-    } else if is_diagonal(position1, position2) {
-        true
-    } else {
-        false
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
     }
 }
 
+pub fn is_adjacent(position1: &Point, position2: &Point) -> bool {
+    // Overlapping, touching and diagonal positions are all adjacent.
+    let difference = *position1 - *position2;
+    difference.x.abs() <= 1 && difference.y.abs() <= 1
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_is_adjacent() {
-        assert_eq!(true, is_adjacent(&(2, 3), &(3, 3)));
-        assert_eq!(true, is_adjacent(&(3, 2), &(3, 3)));
-        assert_eq!(false, is_adjacent(&(2, 3), &(4, 3)));
-        assert_eq!(false, is_adjacent(&(3, 2), &(5, 5)));
+        assert!(is_adjacent(&Point::new(2, 3), &Point::new(3, 3)));
+        assert!(is_adjacent(&Point::new(3, 2), &Point::new(3, 3)));
+        assert!(!is_adjacent(&Point::new(2, 3), &Point::new(4, 3)));
+        assert!(!is_adjacent(&Point::new(3, 2), &Point::new(5, 5)));
     }
 
     #[test]
-    fn test_is_diagonal() {
-        assert_eq!(true, is_diagonal(&(1, 1), &(2, 2)));
-        assert_eq!(false, is_diagonal(&(1, 1), &(3, 3)));
-        assert_eq!(false, is_diagonal(&(1, 1), &(3, 2)));
+    fn test_is_adjacent_diagonally() {
+        assert!(is_adjacent(&Point::new(1, 1), &Point::new(2, 2)));
+        assert!(!is_adjacent(&Point::new(1, 1), &Point::new(3, 3)));
+        assert!(!is_adjacent(&Point::new(1, 1), &Point::new(3, 2)));
     }
 }
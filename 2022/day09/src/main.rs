@@ -1,17 +1,54 @@
-use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
 mod helpers;
+mod render;
+mod rope;
+mod tui;
+
+use render::{render_ansi, visualize, write_png, GifRecorder, Rgb};
 
 // Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
+// Print every step of the simulation as an ASCII frame. Only feasible on the test inputs.
+const ANIMATE: bool = false;
+// Number of head steps between frames of the animation written with `--visualize`.
+const STEPS_PER_FRAME: u32 = 20;
+
+/// A rectangular grid stored row-major in a single vector, indexed by `(row, column)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "Grid should have width * height cells."
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+}
+
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
     let contents: String =
@@ -19,123 +56,85 @@ fn read_file(file_path_as_str: &str) -> String {
     contents
 }
 
-fn move_position(position: &mut (i32, i32), direction: &str) {
-    match direction {
-        "L" => position.0 = position.0 - 1,
-        "R" => position.0 = position.0 + 1,
-        "U" => position.1 = position.1 + 1,
-        "D" => position.1 = position.1 - 1,
-        _ => {
-            println!("ERROR: Illegal move!")
-        }
-    };
+fn solve_part_1(contents: &str) -> usize {
+    rope::simulate(&rope::parse_motions(contents), 2, ANIMATE)
 }
 
-fn move_tail(leader: &(i32, i32), follower: &mut (i32, i32)) {
-    if !helpers::is_adjacent(leader, follower) {
-        // Higher row.
-        if leader.1 > follower.1 {
-            move_position(follower, "U");
-        // Lower row.
-        } else if leader.1 < follower.1 {
-            move_position(follower, "D");
-        } else {
-        }
-        // More right column.
-        if leader.0 > follower.0 {
-            move_position(follower, "R");
-        // More left column.
-        } else if leader.0 < follower.0 {
-            move_position(follower, "L");
-        } else {
-        }
-    }
+fn solve_part_2(contents: &str) -> usize {
+    rope::simulate(&rope::parse_motions(contents), 10, ANIMATE)
 }
 
-fn solve_part_1(contents: &String) -> usize {
-    // Keep track of solution.
-    let start_position: (i32, i32) = (0, 0);
-    let mut visited_positions: HashSet<(i32, i32)> = HashSet::new();
-    let mut head_position = start_position.clone();
-    let mut tail_position = start_position.clone();
-
-    // Parse line by line.
-    for line in contents.lines() {
-        let instructions: Vec<&str> = line.split_whitespace().collect();
-        let direction: &str = instructions[0];
-        let steps: i32 = instructions[1].parse().unwrap();
-        for _ in 0..steps {
-            move_position(&mut head_position, direction);
-            move_tail(&head_position, &mut tail_position);
-
-            // Track all tail positions.
-            visited_positions.insert(tail_position);
-        }
+fn cell_color(cell: &char) -> Rgb {
+    match cell {
+        'H' => [220, 50, 50],
+        's' => [60, 110, 200],
+        '#' => [150, 150, 150],
+        '.' => [30, 30, 30],
+        // The knots following the head.
+        _ => [250, 170, 60],
     }
-
-    visited_positions.len()
 }
 
-fn solve_part_2(contents: &String) -> usize {
-    // // Keep track of solution.
-    let start_position: (i32, i32) = (0, 0);
-    let mut visited_positions: HashSet<(i32, i32)> = HashSet::new();
-    let mut head_position = start_position.clone();
-    // Set up all possible tail positions.
-    let mut tail_positions: HashMap<u8, (i32, i32)> = HashMap::new();
-    for i in 1..10 {
-        tail_positions.insert(i, start_position.clone());
-    }
-
-    // Parse line by line.
-    for line in contents.lines() {
-        let instructions: Vec<&str> = line.split_whitespace().collect();
-        let direction: &str = instructions[0];
-        let steps: i32 = instructions[1].parse().unwrap();
-        for _ in 0..steps {
-            move_position(&mut head_position, direction);
-            for i in 1..10 {
-                // Get previous knot.
-                let leader: (i32, i32);
-                if i == 1 {
-                    leader = head_position;
-                } else {
-                    let previous = tail_positions.get(&(i - 1)).unwrap().clone();
-                    leader = previous;
-                }
-
-                // Get the follower knot.
-                let follower = tail_positions.get_mut(&i).unwrap();
-
-                // Update the follower knot given the leader knot.
-                move_tail(&leader, follower);
-
-                // Track all tail positions.
-                if i == 9 {
-                    visited_positions.insert(follower.clone());
-                }
+/// Writes the motion of a rope to `visualizations/rope.gif`, and prints its final state in the
+/// terminal and writes it to `visualizations/rope.png`.
+fn visualize_rope(motions: &[rope::Motion], knot_count: usize) -> Result<(), String> {
+    // Simulate once to find how far the rope gets, so every frame has the same size.
+    let mut rope = rope::Rope::new(knot_count);
+    motions.iter().for_each(|motion| rope.apply(motion));
+    let (min, max) = rope.bounds();
+
+    let final_frame = rope.frame(min, max);
+    let mut recorder = GifRecorder::create(
+        Path::new("visualizations/rope.gif"),
+        final_frame.width,
+        final_frame.height,
+        2,
+        4,
+    )?;
+    let mut rope = rope::Rope::new(knot_count);
+    let mut steps = 0;
+    for motion in motions {
+        for _ in 0..motion.steps {
+            rope.step(motion.direction);
+            steps += 1;
+            if steps % STEPS_PER_FRAME == 0 {
+                recorder.add_frame(&rope.frame(min, max), cell_color)?;
             }
         }
     }
-
-    visited_positions.len()
+    recorder.add_frame(&final_frame, cell_color)?;
+
+    print!(
+        "{}",
+        render_ansi(&final_frame, |cell| (cell_color(cell), *cell))
+    );
+    write_png(
+        Path::new("visualizations/rope.png"),
+        &final_frame,
+        2,
+        cell_color,
+    )
 }
 
 fn main() {
     // Initialize problem.
-    let contents: String;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
-            // contents = read_file("data/test2.txt");
-        }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
-        }
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Mode::Test => read_file("data/test2.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
+
+    // Step through the longer rope instead of solving.
+    if tui::interactive() {
+        let simulation = rope::Simulation::new(rope::parse_motions(&contents), 10);
+        tui::run_interactive("Rope of 10 knots", simulation).expect("Terminal UI failed.");
+        return;
     }
 
+    println!("Mode: {:?}", CHOSEN_MODE);
+
     // Part 1.
     let part_1_score = solve_part_1(&contents);
     println!("Part 1 - Solution: {}", part_1_score);
@@ -143,4 +142,10 @@ fn main() {
     // Part 2.
     let part_2_score = solve_part_2(&contents);
     println!("Part 2 - Solution: {}", part_2_score);
+
+    if visualize() {
+        if let Err(error) = visualize_rope(&rope::parse_motions(&contents), 10) {
+            println!("WARNING: {}", error);
+        }
+    }
 }
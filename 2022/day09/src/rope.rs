@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::helpers::{self, Point};
+use crate::tui::Steppable;
+use crate::Grid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    fn offset(&self) -> Point {
+        match self {
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+            Direction::Up => Point::new(0, 1),
+            Direction::Down => Point::new(0, -1),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            "U" => Ok(Direction::Up),
+            "D" => Ok(Direction::Down),
+            _ => Err(format!("Illegal direction: {s}")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Motion {
+    pub direction: Direction,
+    pub steps: u32,
+}
+
+impl FromStr for Motion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (direction, steps) = s
+            .split_once(' ')
+            .ok_or_else(|| format!("Illegal motion: {s}"))?;
+        Ok(Motion {
+            direction: direction.parse()?,
+            steps: steps
+                .trim()
+                .parse()
+                .map_err(|_| format!("Illegal number of steps: {steps}"))?,
+        })
+    }
+}
+
+pub fn parse_motions(contents: &str) -> Vec<Motion> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse().expect("Should have been a valid motion."))
+        .collect()
+}
+
+/// A rope of `knot_count` knots, where knot 0 is the head and the last knot is the tail.
+/// All knots start at the origin and every position a knot ever occupies is recorded.
+#[derive(Clone)]
+pub struct Rope {
+    knots: Vec<Point>,
+    visited: Vec<HashSet<Point>>,
+}
+
+impl Rope {
+    pub fn new(knot_count: usize) -> Self {
+        assert!(knot_count > 0, "A rope needs at least one knot.");
+        let start = Point::default();
+        Rope {
+            knots: vec![start; knot_count],
+            visited: vec![HashSet::from([start]); knot_count],
+        }
+    }
+
+    pub fn visited(&self, knot: usize) -> &HashSet<Point> {
+        &self.visited[knot]
+    }
+
+    pub fn tail_visited(&self) -> &HashSet<Point> {
+        self.visited(self.knots.len() - 1)
+    }
+
+    /// Move the head one unit and let every following knot catch up with its leader.
+    pub fn step(&mut self, direction: Direction) {
+        self.knots[0] = self.knots[0] + direction.offset();
+        self.visited[0].insert(self.knots[0]);
+
+        for i in 1..self.knots.len() {
+            let leader = self.knots[i - 1];
+            let follower = self.knots[i];
+            if helpers::is_adjacent(&leader, &follower) {
+                // Knots further down the rope cannot move either.
+                break;
+            }
+            self.knots[i] = follower + (leader - follower).signum();
+            self.visited[i].insert(self.knots[i]);
+        }
+    }
+
+    pub fn apply(&mut self, motion: &Motion) {
+        for _ in 0..motion.steps {
+            self.step(motion.direction);
+        }
+    }
+
+    /// The lowest and highest coordinates that any knot has visited so far.
+    pub fn bounds(&self) -> (Point, Point) {
+        let visited = self.visited.iter().flatten();
+        let min_x = visited.clone().map(|p| p.x).min().unwrap_or(0);
+        let max_x = visited.clone().map(|p| p.x).max().unwrap_or(0);
+        let min_y = visited.clone().map(|p| p.y).min().unwrap_or(0);
+        let max_y = visited.map(|p| p.y).max().unwrap_or(0);
+        (Point::new(min_x, min_y), Point::new(max_x, max_y))
+    }
+
+    /// The rope as a grid of characters from `min` to `max`, in the style of the puzzle
+    /// description, with the highest y in the top row.
+    pub fn frame(&self, min: Point, max: Point) -> Grid<char> {
+        let mut cells = Vec::new();
+        for y in (min.y..=max.y).rev() {
+            for x in min.x..=max.x {
+                let point = Point::new(x, y);
+                // Earlier knots are drawn on top of later ones.
+                let character = match self.knots.iter().position(|&knot| knot == point) {
+                    Some(0) => 'H',
+                    Some(i) => char::from_digit((i % 36) as u32, 36).unwrap_or('#'),
+                    None if point == Point::default() => 's',
+                    None if self.tail_visited().contains(&point) => '#',
+                    None => '.',
+                };
+                cells.push(character);
+            }
+        }
+        Grid::new(
+            (max.x - min.x + 1) as usize,
+            (max.y - min.y + 1) as usize,
+            cells,
+        )
+    }
+
+    /// Render the rope as ASCII. The frame spans the start and everything visited so far, so it
+    /// only grows.
+    pub fn render(&self) -> String {
+        let (min, max) = self.bounds();
+        let mut frame = String::new();
+        for row in self.frame(min, max).rows() {
+            frame.extend(row);
+            frame.push('\n');
+        }
+        frame
+    }
+}
+
+/// A rope following its motions one unit at a time, to step through in the terminal.
+#[derive(Clone)]
+pub struct Simulation {
+    rope: Rope,
+    motions: Vec<Motion>,
+    // The motion being applied and how many of its steps are done.
+    motion: usize,
+    done: u32,
+}
+
+impl Simulation {
+    pub fn new(motions: Vec<Motion>, knot_count: usize) -> Self {
+        Simulation {
+            rope: Rope::new(knot_count),
+            motions,
+            motion: 0,
+            done: 0,
+        }
+    }
+}
+
+impl Steppable for Simulation {
+    fn step(&mut self) -> bool {
+        // Skip motions that are done, including any of zero steps.
+        while self
+            .motions
+            .get(self.motion)
+            .is_some_and(|motion| self.done == motion.steps)
+        {
+            self.motion += 1;
+            self.done = 0;
+        }
+        let Some(motion) = self.motions.get(self.motion) else {
+            return false;
+        };
+        self.rope.step(motion.direction);
+        self.done += 1;
+        true
+    }
+
+    fn inspect(&self) -> Vec<String> {
+        let mut lines = match self.motions.get(self.motion) {
+            Some(motion) => vec![format!(
+                "Motion {} of {}: {:?} {}, {} of {} steps done",
+                self.motion + 1,
+                self.motions.len(),
+                motion.direction,
+                motion.steps,
+                self.done,
+                motion.steps
+            )],
+            None => vec!["No motions left".to_string()],
+        };
+        for (i, knot) in self.rope.knots.iter().enumerate() {
+            let name = if i == 0 {
+                "H".to_string()
+            } else {
+                i.to_string()
+            };
+            lines.push(format!("Knot {name}: ({}, {})", knot.x, knot.y));
+        }
+        lines.push(format!(
+            "Tail visited {} positions",
+            self.rope.tail_visited().len()
+        ));
+        lines.push(String::new());
+        lines.extend(self.rope.render().lines().map(str::to_string));
+        lines
+    }
+}
+
+/// Simulate a rope of `knot_count` knots and return the number of positions visited by its tail.
+/// When `animate` is set, every step is printed as a separate frame.
+pub fn simulate(motions: &[Motion], knot_count: usize, animate: bool) -> usize {
+    let mut rope = Rope::new(knot_count);
+    for motion in motions {
+        if animate {
+            for _ in 0..motion.steps {
+                rope.step(motion.direction);
+                // Clear the terminal and move the cursor to the top left before drawing.
+                print!("\x1B[2J\x1B[H{}", rope.render());
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+        } else {
+            rope.apply(motion);
+        }
+    }
+    rope.tail_visited().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::{assert_rewinds, Session};
+
+    const TEST_INPUT: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2\n";
+    const LARGER_TEST_INPUT: &str = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n";
+
+    #[test]
+    fn test_parse_motion() {
+        let motion: Motion = "U 12".parse().unwrap();
+        assert_eq!(Direction::Up, motion.direction);
+        assert_eq!(12, motion.steps);
+        assert!("X 1".parse::<Motion>().is_err());
+        assert!("R".parse::<Motion>().is_err());
+    }
+
+    #[test]
+    fn test_simulate() {
+        assert_eq!(13, simulate(&parse_motions(TEST_INPUT), 2, false));
+        assert_eq!(1, simulate(&parse_motions(TEST_INPUT), 10, false));
+        assert_eq!(36, simulate(&parse_motions(LARGER_TEST_INPUT), 10, false));
+    }
+
+    #[test]
+    fn test_visited_of_any_knot() {
+        let mut rope = Rope::new(10);
+        for motion in parse_motions(TEST_INPUT) {
+            rope.apply(&motion);
+        }
+        // A rope of two knots has the same tail as the second knot of a longer rope.
+        assert_eq!(13, rope.visited(1).len());
+        assert_eq!(rope.visited(9), rope.tail_visited());
+    }
+
+    #[test]
+    fn test_simulation_steps() {
+        let mut simulation = Simulation::new(parse_motions(TEST_INPUT), 2);
+        let mut steps = 0;
+        while simulation.step() {
+            steps += 1;
+        }
+        assert_eq!(24, steps);
+        assert_eq!(13, simulation.rope.tail_visited().len());
+        assert!(simulation.inspect().contains(&"Knot H: (2, 2)".to_string()));
+    }
+
+    #[test]
+    fn test_simulation_rewind() {
+        // Long enough to pass the first snapshot of the session.
+        let motions = parse_motions(&LARGER_TEST_INPUT.repeat(2));
+        let session = Session::new(Simulation::new(motions, 10));
+        assert_eq!(192, assert_rewinds(session));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut rope = Rope::new(3);
+        rope.apply(&"R 2".parse().unwrap());
+        assert_eq!("21H\n", rope.render());
+        // A fixed frame can be larger than what the rope has visited so far.
+        let frame = rope.frame(Point::new(-1, 0), Point::new(2, 1));
+        assert_eq!(
+            vec![&['.'; 4][..], &['.', '2', '1', 'H']],
+            frame.rows().collect::<Vec<_>>()
+        );
+    }
+}
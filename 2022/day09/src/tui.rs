@@ -0,0 +1,241 @@
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::Print;
+use crossterm::{cursor, execute, queue, terminal};
+
+/// A simulation that the terminal UI can advance one step at a time.
+pub trait Steppable: Clone {
+    /// Advances the simulation by one step, or returns `false` without changing anything once it
+    /// has finished.
+    fn step(&mut self) -> bool;
+
+    /// The state after the current step, one line per entry.
+    fn inspect(&self) -> Vec<String>;
+}
+
+// A snapshot is kept every this many steps and rewinding replays from the closest one, so only a
+// hundredth of the states is stored. Snapshots are never dropped, so memory still grows with the
+// number of steps.
+const SNAPSHOT_INTERVAL: usize = 100;
+// Time between redraws while running.
+const RUN_DELAY: Duration = Duration::from_millis(50);
+// Steps taken per redraw can be doubled up to this many.
+const MAX_SPEED: usize = 4096;
+
+/// A simulation together with snapshots of its earlier states, so it can be stepped back.
+pub struct Session<S> {
+    state: S,
+    steps: usize,
+    finished: bool,
+    // The state after every multiple of `SNAPSHOT_INTERVAL` steps, starting with the first.
+    snapshots: Vec<S>,
+}
+
+impl<S: Steppable> Session<S> {
+    pub fn new(state: S) -> Self {
+        Session {
+            snapshots: vec![state.clone()],
+            state,
+            steps: 0,
+            finished: false,
+        }
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn step(&mut self) -> bool {
+        if self.finished || !self.state.step() {
+            self.finished = true;
+            return false;
+        }
+        self.steps += 1;
+        // After a rewind, the snapshots ahead are still valid, as steps are deterministic.
+        if self.steps.is_multiple_of(SNAPSHOT_INTERVAL)
+            && self.snapshots.len() == self.steps / SNAPSHOT_INTERVAL
+        {
+            self.snapshots.push(self.state.clone());
+        }
+        true
+    }
+
+    /// Undoes the last step, or returns `false` at the start.
+    pub fn rewind(&mut self) -> bool {
+        if self.steps == 0 {
+            return false;
+        }
+        let target = self.steps - 1;
+        self.state = self.snapshots[target / SNAPSHOT_INTERVAL].clone();
+        self.steps = target / SNAPSHOT_INTERVAL * SNAPSHOT_INTERVAL;
+        self.finished = false;
+        while self.steps < target {
+            self.step();
+        }
+        true
+    }
+}
+
+/// Steps `session` to the end and back to the start, checking that every rewind restores the state
+/// seen at that step on the way forward. Returns the number of steps taken.
+#[cfg(test)]
+pub fn assert_rewinds<S: Steppable>(mut session: Session<S>) -> usize {
+    let mut states = vec![session.state().inspect()];
+    while session.step() {
+        states.push(session.state().inspect());
+    }
+    for expected in states.iter().rev().skip(1) {
+        assert!(session.rewind());
+        assert_eq!(*expected, session.state().inspect());
+    }
+    assert!(!session.rewind());
+    states.len() - 1
+}
+
+fn terminal_error(error: io::Error) -> String {
+    format!("Terminal error: {error}")
+}
+
+fn draw<S: Steppable>(
+    stdout: &mut Stdout,
+    title: &str,
+    session: &Session<S>,
+    status: &str,
+) -> Result<(), String> {
+    let (width, height) = terminal::size().map_err(terminal_error)?;
+    let mut lines = vec![
+        format!("{title} - step {} ({status})", session.steps()),
+        "space: run/pause  right/s: step  left/b: rewind  +/-: speed  q: quit".to_string(),
+        String::new(),
+    ];
+    lines.extend(session.state().inspect());
+
+    queue!(stdout, terminal::Clear(terminal::ClearType::All)).map_err(terminal_error)?;
+    for (row, line) in lines.iter().take(height as usize).enumerate() {
+        // Anything beyond the edge of the terminal is cut off rather than wrapped.
+        let line: String = line.chars().take(width as usize).collect();
+        queue!(stdout, cursor::MoveTo(0, row as u16), Print(line)).map_err(terminal_error)?;
+    }
+    stdout.flush().map_err(terminal_error)
+}
+
+fn event_loop<S: Steppable>(
+    stdout: &mut Stdout,
+    title: &str,
+    mut session: Session<S>,
+) -> Result<(), String> {
+    let mut running = false;
+    let mut speed = 1;
+    loop {
+        let status = if running {
+            format!("running x{speed}")
+        } else if session.finished {
+            "finished".to_string()
+        } else {
+            "paused".to_string()
+        };
+        draw(stdout, title, &session, &status)?;
+
+        // While paused, block until a key is pressed or the terminal is resized.
+        let timeout = if running {
+            RUN_DELAY
+        } else {
+            Duration::from_secs(3600)
+        };
+        if !event::poll(timeout).map_err(terminal_error)? {
+            if running {
+                running = (0..speed).all(|_| session.step());
+            }
+            continue;
+        }
+        let Event::Key(key) = event::read().map_err(terminal_error)? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char(' ') => running = !running && !session.finished,
+            KeyCode::Right | KeyCode::Char('s') => {
+                running = false;
+                session.step();
+            }
+            KeyCode::Left | KeyCode::Char('b') => {
+                running = false;
+                session.rewind();
+            }
+            KeyCode::Char('+') => speed = (speed * 2).min(MAX_SPEED),
+            KeyCode::Char('-') => speed = (speed / 2).max(1),
+            _ => {}
+        }
+    }
+}
+
+/// Steps through a simulation in the terminal until `q` is pressed.
+pub fn run_interactive<S: Steppable>(title: &str, state: S) -> Result<(), String> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().map_err(terminal_error)?;
+    let result = execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)
+        .map_err(terminal_error)
+        .and_then(|_| event_loop(&mut stdout, title, Session::new(state)));
+    // Restore the terminal even when drawing failed, so the error stays readable.
+    let _ = execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    result
+}
+
+/// Whether the solver was started with `--interactive`, to step through it instead of solving.
+pub fn interactive() -> bool {
+    std::env::args().any(|argument| argument == "--interactive")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Counter {
+        value: usize,
+        limit: usize,
+    }
+
+    impl Steppable for Counter {
+        fn step(&mut self) -> bool {
+            if self.value == self.limit {
+                return false;
+            }
+            self.value += 1;
+            true
+        }
+
+        fn inspect(&self) -> Vec<String> {
+            vec![self.value.to_string()]
+        }
+    }
+
+    #[test]
+    fn test_step_and_rewind_across_snapshots() {
+        let limit = 2 * SNAPSHOT_INTERVAL + 5;
+        let mut session = Session::new(Counter { value: 0, limit });
+        while session.step() {}
+        assert_eq!(limit, session.steps());
+        assert!(!session.step());
+
+        // Rewinding replays from the closest snapshot, back past several of them.
+        for expected in (0..limit).rev() {
+            assert!(session.rewind());
+            assert_eq!(expected, session.state().value);
+            assert_eq!(expected, session.steps());
+        }
+        assert!(!session.rewind());
+        assert!(session.step());
+        assert_eq!(vec!["1"], session.state().inspect());
+    }
+}
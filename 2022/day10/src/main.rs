@@ -1,52 +1,31 @@
 use std::fs;
 use std::path::Path;
 
+mod tui;
+
+use tui::Steppable;
+
 // Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
-    let contents: String =
-        fs::read_to_string(file_path).expect("Should have been able to read the file.");
-    contents
+    fs::read_to_string(file_path).expect("Should have been able to read the file.")
 }
 
 fn increase_cycle(cycle: &mut i32, value_of_x: &i32) -> i32 {
-    let signal_strength;
     *cycle += 1;
     if (*cycle - 20) % 40 == 0 {
-        signal_strength = *cycle * value_of_x;
+        *cycle * value_of_x
     } else {
-        signal_strength = 0;
+        0
     }
-
-    signal_strength
-}
-
-fn solve_part_1(contents: &String) -> i32 {
-    // Keep track of solution.
-    let mut cycle: i32 = 0;
-    let mut value_of_x: i32 = 1;
-    let mut signal_strength: i32 = 0;
-
-    // Parse line by line.
-    for line in contents.lines() {
-        if line == "noop" {
-            signal_strength += increase_cycle(&mut cycle, &value_of_x);
-        } else {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            signal_strength += increase_cycle(&mut cycle, &value_of_x);
-            signal_strength += increase_cycle(&mut cycle, &value_of_x);
-            value_of_x += parts[1].parse::<i32>().unwrap();
-        }
-    }
-
-    signal_strength
 }
 
 fn evaluate_screen(cycle: &i32, value_of_x: &i32) -> String {
@@ -63,57 +42,163 @@ fn evaluate_screen(cycle: &i32, value_of_x: &i32) -> String {
     }
 }
 
-fn solve_part_2(contents: &String) {
-    // Keep track of solution.
-    let mut cycle: i32 = 1;
-    let mut value_of_x: i32 = 1;
-    let mut screen: Vec<String> = Vec::new();
+/// The CPU running a program, one cycle at a time, and the screen it draws.
+#[derive(Clone)]
+struct Cpu {
+    program: Vec<String>,
+    // The instruction being executed, and whether an `addx` has finished its first cycle.
+    instruction: usize,
+    halfway: bool,
+    cycle: i32,
+    value_of_x: i32,
+    signal_strength: i32,
+    screen: Vec<String>,
+}
+
+impl Cpu {
+    fn new(contents: &str) -> Self {
+        Cpu {
+            program: contents.lines().map(|line| line.to_string()).collect(),
+            instruction: 0,
+            halfway: false,
+            cycle: 0,
+            value_of_x: 1,
+            signal_strength: 0,
+            screen: Vec::new(),
+        }
+    }
+
+    /// The pixels drawn so far, joined into rows of 40.
+    fn image(&self) -> Vec<String> {
+        self.screen.chunks(40).map(|row| row.concat()).collect()
+    }
+}
 
-    // Parse line by line.
-    for line in contents.lines() {
+impl Steppable for Cpu {
+    fn step(&mut self) -> bool {
+        let Some(line) = self.program.get(self.instruction) else {
+            return false;
+        };
+        // X only changes at the end of a cycle, so both parts see its value during the cycle.
+        self.signal_strength += increase_cycle(&mut self.cycle, &self.value_of_x);
+        self.screen
+            .push(evaluate_screen(&self.cycle, &self.value_of_x));
         if line == "noop" {
-            screen.push(evaluate_screen(&cycle, &value_of_x));
-            cycle +=1;
+            self.instruction += 1;
+        } else if !self.halfway {
+            self.halfway = true;
         } else {
-            screen.push(evaluate_screen(&cycle, &value_of_x));
-            cycle +=1;
-            screen.push(evaluate_screen(&cycle, &value_of_x));
-            cycle +=1;
             let parts: Vec<&str> = line.split_whitespace().collect();
-            value_of_x += parts[1].parse::<i32>().unwrap();
+            self.value_of_x += parts[1].parse::<i32>().unwrap();
+            self.halfway = false;
+            self.instruction += 1;
         }
+        true
     }
 
-    // Print output.
-    let mut line: String = "".to_owned();
-    for i in 0..240 {
-        if i % 40 == 0 {
-            println!("{}", line);
-            line = "".to_owned();
-        }
-        line.push_str(&screen[i]);
+    fn inspect(&self) -> Vec<String> {
+        let instruction = match self.program.get(self.instruction) {
+            Some(line) if self.halfway => format!("{line} (second cycle)"),
+            Some(line) => line.clone(),
+            None => "halted".to_string(),
+        };
+        // The sprite is three pixels wide, centred on X.
+        let sprite: String = (0..40)
+            .map(|pixel: i32| {
+                if (pixel - self.value_of_x).abs() <= 1 {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        let mut lines = vec![
+            format!("Cycles done: {}", self.cycle),
+            format!("Register X: {}", self.value_of_x),
+            format!(
+                "Next instruction {} of {}: {instruction}",
+                self.instruction + 1,
+                self.program.len()
+            ),
+            format!("Signal strength: {}", self.signal_strength),
+            String::new(),
+            format!("Sprite: {sprite}"),
+            String::new(),
+        ];
+        lines.extend(self.image());
+        lines
     }
-    println!("{}", line);
+}
+
+fn solve_part_1(contents: &str) -> i32 {
+    let mut cpu = Cpu::new(contents);
+    while cpu.step() {}
+    cpu.signal_strength
+}
+
+/// The image drawn on the screen, one line of 40 pixels per row.
+fn solve_part_2(contents: &str) -> String {
+    let mut cpu = Cpu::new(contents);
+    while cpu.step() {}
+    cpu.image().join("\n")
 }
 
 fn main() {
     // Initialize problem.
-    let contents: String;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
-        }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
-        }
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
+
+    // Step through the program instead of solving.
+    if tui::interactive() {
+        tui::run_interactive("CPU", Cpu::new(&contents)).expect("Terminal UI failed.");
+        return;
     }
 
+    println!("Mode: {:?}", CHOSEN_MODE);
+
     // Part 1.
     let part_1_score = solve_part_1(&contents);
     println!("Part 1 - Solution: {}", part_1_score);
 
     // Part 2.
-    solve_part_2(&contents);
+    let part_2_image = solve_part_2(&contents);
+    println!("Part 2 - Solution:\n{}", part_2_image);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::{assert_rewinds, Session};
+
+    #[test]
+    fn test_solve() {
+        let contents = read_file("data/test.txt");
+        assert_eq!(13140, solve_part_1(&contents));
+        assert_eq!(
+            "##..##..##..##..##..##..##..##..##..##..",
+            solve_part_2(&contents).lines().next().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cpu_rewind() {
+        let session = Session::new(Cpu::new(&read_file("data/test.txt")));
+        assert_eq!(240, assert_rewinds(session));
+    }
+
+    #[test]
+    fn test_cpu_steps() {
+        let mut cpu = Cpu::new("noop\naddx 3\naddx -5\n");
+        let mut values = Vec::new();
+        while cpu.step() {
+            values.push(cpu.value_of_x);
+        }
+        // X changes after the two cycles of each `addx`.
+        assert_eq!(vec![1, 1, 4, 4, -1], values);
+        assert!(cpu.inspect().contains(&"Register X: -1".to_string()));
+    }
 }
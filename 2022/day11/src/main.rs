@@ -2,30 +2,68 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+mod tui;
+
+use tui::Steppable;
+
 // Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
-// Magic numbers.
-const NUMBER_OF_ROUNDS: usize = 10000;
-const WORRY_DIVISOR: u64 = 1;
+/// How many rounds the monkeys play, and what worry levels are divided by after every inspection.
+#[derive(Clone, Copy, Debug)]
+struct Rules {
+    rounds: usize,
+    worry_divisor: u64,
+}
+
+const PART_1_RULES: Rules = Rules {
+    rounds: 20,
+    worry_divisor: 3,
+};
+const PART_2_RULES: Rules = Rules {
+    rounds: 10000,
+    worry_divisor: 1,
+};
 
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
     fs::read_to_string(file_path).expect("Should have been able to read the file.")
 }
 
-fn parse_data(
-    contents: &String,
-) -> (
+type ParsedMonkeys<'a> = (
     HashMap<u64, Vec<u64>>,
     HashMap<u64, Vec<String>>,
-    HashMap<u64, HashMap<&str, u64>>,
-) {
+    HashMap<u64, HashMap<&'a str, u64>>,
+);
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Like `lcm`, but returns `None` instead of overflowing.
+fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+fn checked_lcm_of_vec(numbers: &[u64]) -> Option<u64> {
+    numbers
+        .iter()
+        .try_fold(1, |result, &number| checked_lcm(result, number))
+}
+
+fn parse_data(contents: &str) -> ParsedMonkeys<'_> {
     // Define structures to hold parsed data.
     let mut monkey_item_map: HashMap<u64, Vec<u64>> = HashMap::new();
     let mut monkey_operations: HashMap<u64, Vec<String>> = HashMap::new();
@@ -69,7 +107,7 @@ fn parse_data(
             let test_specs =
                 HashMap::from([("test", test), ("true", if_true), ("false", if_false)]);
             monkey_tests.insert(monkey_id, test_specs);
-        } else if line == "" {
+        } else if line.is_empty() {
             // Skip.
         } else {
             println!("WARNING: Unexpected line!");
@@ -78,123 +116,263 @@ fn parse_data(
     (monkey_item_map, monkey_operations, monkey_tests)
 }
 
-fn solve_part_1(
-    monkey_item_map: &mut HashMap<u64, Vec<u64>>,
+/// The monkeys playing keep away, one turn at a time.
+#[derive(Clone)]
+struct KeepAway<'a> {
+    monkey_item_map: HashMap<u64, Vec<u64>>,
     monkey_operations: HashMap<u64, Vec<String>>,
-    monkey_tests: HashMap<u64, HashMap<&str, u64>>,
-) -> u64 {
+    monkey_tests: HashMap<u64, HashMap<&'a str, u64>>,
     // Keep track of the number of inspections a monkey performed.
-    let mut monkey_inspections: HashMap<u64, u64> = HashMap::new();
-    // Initialize inspection count at 0.
-    for monkey in 0..monkey_item_map.len() {
-        monkey_inspections.insert(monkey as u64, 0);
-    }
+    monkey_inspections: HashMap<u64, u64>,
+    least_common_multiple: u64,
+    rules: Rules,
+    // Rounds completed and the monkey whose turn is next.
+    round: usize,
+    monkey: u64,
+}
+
+impl<'a> KeepAway<'a> {
+    fn new(
+        monkey_item_map: HashMap<u64, Vec<u64>>,
+        monkey_operations: HashMap<u64, Vec<String>>,
+        monkey_tests: HashMap<u64, HashMap<&'a str, u64>>,
+        rules: Rules,
+    ) -> Self {
+        // Initialize inspection count at 0.
+        let monkey_inspections: HashMap<u64, u64> = (0..monkey_item_map.len())
+            .map(|monkey| (monkey as u64, 0))
+            .collect();
 
-    // Compute least common multiple to keep worry levels from overflowing.
-    let mut least_common_multiple = 1;
-    for monkey in 0..monkey_tests.len() {
-        let test = monkey_tests.get(&(monkey as u64)).unwrap();
-        let denominator = test.get("test").unwrap();
-        least_common_multiple *= *denominator;
+        // Worry levels only matter modulo every test divisor, so reduce them by the least common
+        // multiple of all divisors to keep them from overflowing.
+        let denominators: Vec<u64> = (0..monkey_tests.len())
+            .map(|monkey| monkey_tests[&(monkey as u64)]["test"])
+            .collect();
+        let least_common_multiple =
+            checked_lcm_of_vec(&denominators).expect("Test divisors should have a u64 lcm.");
+
+        KeepAway {
+            monkey_item_map,
+            monkey_operations,
+            monkey_tests,
+            monkey_inspections,
+            least_common_multiple,
+            rules,
+            round: 0,
+            monkey: 0,
+        }
     }
 
-    // Loop over the rounds.
-    for _ in 0..NUMBER_OF_ROUNDS {
-        // Every monkey plays once during a round.
-        for monkey in 0..monkey_item_map.len() {
-            // println!("Monkey {}:", monkey);
-            let monkey_id: &u64 = &(monkey as u64);
-            // Inspect all items a monkey holds.
-            let items = monkey_item_map.get(monkey_id).unwrap().clone();
-            for item in items.iter() {
-                // Increase monkey inspection count.
-                let monkey_inspection_count = monkey_inspections.get(&monkey_id).unwrap();
-                monkey_inspections.insert(*monkey_id, monkey_inspection_count + 1);
-                // println!("  Monkey inspects an item with a worry level of {}.", item);
-                let operation = monkey_operations.get(monkey_id).unwrap();
-                // Parse left hand and right hand information.
-                if operation[0] != "old" {
-                    println!("ERROR: Unexpected input!");
-                }
-                let right_element = if operation[2] == "old" {
-                    *item
-                } else {
-                    operation[2].parse().unwrap()
-                };
-                // Apply operator.
-                let mut worry_level: u64 = match &operation[1][..] {
-                    "*" => {
-                        let result = item * right_element;
-                        // println! {"    Worry level is multiplied by {} to {}.", right_element, result};
-                        result
-                    }
-                    "+" => {
-                        let result = item + right_element;
-                        // println! {"    Worry level increases by {} to {}.", right_element, result};
-                        result
-                    }
-                    _ => {
-                        println! {"ERROR: Unexpected operator!"};
-                        0
-                    }
-                };
-                // Apply test.
-                let test = monkey_tests.get(monkey_id).unwrap();
-                worry_level /= WORRY_DIVISOR;
-                // println! {"    Monkey gets bored with item. Worry level is divided by 3 to {}.", worry_level};
-                let denominator = test.get("test").unwrap();
-                let throw_to: &u64 = if worry_level % denominator == 0 {
-                    // println!("    Current worry level is divisible by {}.", denominator);
-                    test.get("true").unwrap()
-                } else {
-                    // println!("    Current worry level is not divisible by {}.", denominator);
-                    test.get("false").unwrap()
-                };
-                let throw_to_item_map = monkey_item_map.get_mut(throw_to).unwrap();
-                // println!("    Item with worry level {} is thrown to monkey {}.", worry_level, throw_to);
-                worry_level = if WORRY_DIVISOR == 1 {
-                    worry_level % least_common_multiple
-                } else {
-                    worry_level
-                };
-                throw_to_item_map.push(worry_level);
+    /// The current monkey inspects and throws all items it holds.
+    fn play_turn(&mut self) {
+        // println!("Monkey {}:", monkey);
+        let monkey_id: &u64 = &self.monkey;
+        // Inspect all items a monkey holds.
+        let items = self.monkey_item_map.get(monkey_id).unwrap().clone();
+        for item in items.iter() {
+            // Increase monkey inspection count.
+            let monkey_inspection_count = self.monkey_inspections.get(monkey_id).unwrap();
+            self.monkey_inspections
+                .insert(*monkey_id, monkey_inspection_count + 1);
+            // println!("  Monkey inspects an item with a worry level of {}.", item);
+            let operation = self.monkey_operations.get(monkey_id).unwrap();
+            // Parse left hand and right hand information.
+            if operation[0] != "old" {
+                println!("ERROR: Unexpected input!");
             }
-            monkey_item_map.insert(*monkey_id, Vec::from([]));
+            let right_element = if operation[2] == "old" {
+                *item
+            } else {
+                operation[2].parse().unwrap()
+            };
+            // Apply operator.
+            let mut worry_level: u64 = match &operation[1][..] {
+                "*" => {
+                    let result = item * right_element;
+                    // println! {"    Worry level is multiplied by {} to {}.", right_element, result};
+                    result
+                }
+                "+" => {
+                    let result = item + right_element;
+                    // println! {"    Worry level increases by {} to {}.", right_element, result};
+                    result
+                }
+                _ => {
+                    println! {"ERROR: Unexpected operator!"};
+                    0
+                }
+            };
+            // Apply test.
+            let test = self.monkey_tests.get(monkey_id).unwrap();
+            worry_level /= self.rules.worry_divisor;
+            // println! {"    Monkey gets bored with item. Worry level is divided by 3 to {}.", worry_level};
+            let denominator = test.get("test").unwrap();
+            let throw_to: &u64 = if worry_level.is_multiple_of(*denominator) {
+                // println!("    Current worry level is divisible by {}.", denominator);
+                test.get("true").unwrap()
+            } else {
+                // println!("    Current worry level is not divisible by {}.", denominator);
+                test.get("false").unwrap()
+            };
+            let throw_to_item_map = self.monkey_item_map.get_mut(throw_to).unwrap();
+            // println!("    Item with worry level {} is thrown to monkey {}.", worry_level, throw_to);
+            worry_level = if self.rules.worry_divisor == 1 {
+                worry_level % self.least_common_multiple
+            } else {
+                worry_level
+            };
+            throw_to_item_map.push(worry_level);
+        }
+        self.monkey_item_map.insert(*monkey_id, Vec::from([]));
+
+        // Every monkey plays once during a round.
+        self.monkey += 1;
+        if self.monkey as usize == self.monkey_item_map.len() {
+            self.monkey = 0;
+            self.round += 1;
         }
-        // println!("Round {}: {:?}", round, monkey_item_map);
     }
 
-    // Use inspection count to find the solution.
-    let mut inspections: Vec<u64> = monkey_inspections.values().cloned().collect();
+    fn monkey_business(&self) -> u64 {
+        // Use inspection count to find the solution.
+        let mut inspections: Vec<u64> = self.monkey_inspections.values().cloned().collect();
 
-    // Sort inspections to find top 2.
-    inspections.sort();
-    inspections.reverse();
+        // Sort inspections to find top 2.
+        inspections.sort();
+        inspections.reverse();
 
-    // Multiply to get to final answer.
-    inspections[0] * inspections[1]
+        // Multiply to get to final answer.
+        inspections[0] * inspections[1]
+    }
 }
 
-fn main() {
-    // Initialize problem.
-    let contents: String;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
+impl Steppable for KeepAway<'_> {
+    fn step(&mut self) -> bool {
+        if self.round == self.rules.rounds {
+            return false;
         }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
+        self.play_turn();
+        true
+    }
+
+    fn inspect(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Rounds done: {} of {}", self.round, self.rules.rounds),
+            format!("Next turn: monkey {}", self.monkey),
+            String::new(),
+        ];
+        for monkey in 0..self.monkey_item_map.len() as u64 {
+            let items: Vec<String> = self.monkey_item_map[&monkey]
+                .iter()
+                .map(|item| item.to_string())
+                .collect();
+            lines.push(format!(
+                "Monkey {} (inspected {}): {}",
+                monkey,
+                self.monkey_inspections[&monkey],
+                items.join(", ")
+            ));
         }
+        lines
+    }
+}
+
+/// Plays every round of the game and returns the resulting level of monkey business.
+fn play(mut game: KeepAway) -> u64 {
+    // Loop over the rounds.
+    while game.round < game.rules.rounds {
+        game.play_turn();
     }
+    game.monkey_business()
+}
+
+fn main() {
+    // Initialize problem.
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
 
     let (monkey_item_map, monkey_operations, monkey_tests) = parse_data(&contents);
 
-    let score = solve_part_1(
-        &mut monkey_item_map.clone(),
-        monkey_operations,
-        monkey_tests,
-    );
-    println!("Solution: {}", score);
+    let game = |rules| {
+        KeepAway::new(
+            monkey_item_map.clone(),
+            monkey_operations.clone(),
+            monkey_tests.clone(),
+            rules,
+        )
+    };
+
+    // Step through the rounds of part 2 instead of solving.
+    if tui::interactive() {
+        tui::run_interactive("Keep away", game(PART_2_RULES)).expect("Terminal UI failed.");
+        return;
+    }
+
+    println!("Mode: {:?}", CHOSEN_MODE);
+
+    // Part 1.
+    let part_1_score = play(game(PART_1_RULES));
+    println!("Part 1 - Solution: {}", part_1_score);
+
+    // Part 2.
+    let part_2_score = play(game(PART_2_RULES));
+    println!("Part 2 - Solution: {}", part_2_score);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::{assert_rewinds, Session};
+
+    #[test]
+    fn test_checked_lcm_of_vec() {
+        // Shared factors are only counted once, unlike in the product of all divisors.
+        assert_eq!(Some(60), checked_lcm_of_vec(&[4, 6, 10]));
+        assert_eq!(Some(1), checked_lcm_of_vec(&[]));
+        assert_eq!(None, checked_lcm_of_vec(&[u64::MAX, u64::MAX - 1]));
+    }
+
+    #[test]
+    fn test_keep_away_rewind() {
+        let contents = read_file("data/test.txt");
+        let (monkey_item_map, monkey_operations, monkey_tests) = parse_data(&contents);
+        // Sixty rounds of four turns each, past the first snapshots of the session.
+        let rules = Rules {
+            rounds: 60,
+            worry_divisor: 1,
+        };
+        let game = KeepAway::new(monkey_item_map, monkey_operations, monkey_tests, rules);
+        assert_eq!(240, assert_rewinds(Session::new(game)));
+    }
+
+    #[test]
+    fn test_keep_away_turns() {
+        let contents = read_file("data/test.txt");
+        let (monkey_item_map, monkey_operations, monkey_tests) = parse_data(&contents);
+        let part_1 = KeepAway::new(
+            monkey_item_map.clone(),
+            monkey_operations.clone(),
+            monkey_tests.clone(),
+            PART_1_RULES,
+        );
+        assert_eq!(10_605, play(part_1));
+
+        let mut part_2 = KeepAway::new(
+            monkey_item_map,
+            monkey_operations,
+            monkey_tests,
+            PART_2_RULES,
+        );
+        assert!(part_2.step());
+        // Monkey 0 multiplies both of its items by 19 and throws them to monkey 3.
+        assert_eq!(
+            "Monkey 3 (inspected 0): 74, 1501, 1862",
+            part_2.inspect()[6]
+        );
+        assert_eq!(2_713_310_158, play(part_2));
+    }
 }
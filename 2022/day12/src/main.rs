@@ -1,184 +1,414 @@
-use std::collections::HashSet;
+mod render;
+
+use render::{render_ansi, visualize, write_png, GifRecorder, Rgb};
+use std::collections::VecDeque;
 use std::fs;
 use std::path::Path;
 
 // Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
+// Number of frames in the animation of the search.
+const FRONTIER_FRAMES: u32 = 60;
+
 fn read_file(file_path_as_str: &str) -> String {
     let file_path: &Path = Path::new(file_path_as_str);
     fs::read_to_string(file_path).expect("Should have been able to read the file.")
 }
 
-fn parse_data(contents: &String) -> (Vec<Vec<char>>, (i8, i8), (i8, i8)) {
-    let mut map: Vec<Vec<char>> = Vec::new();
-    let mut start_position: (i8, i8) = (0, 0);
-    let mut end_position: (i8, i8) = (0, 0);
+/// A rectangular grid stored row-major in a single vector, indexed by `(row, column)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
 
-    // Find start and end.
-    for (row_index, line) in contents.lines().enumerate() {
-        for (column_index, character) in line.chars().enumerate() {
-            if character == 'S' {
-                start_position = (row_index as i8, column_index as i8);
-            } else if character == 'E' {
-                end_position = (row_index as i8, column_index as i8);
-            }
+impl<T> Grid<T> {
+    fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "Grid dimensions do not match cells."
+        );
+        Grid {
+            width,
+            height,
+            cells,
         }
-        // Split and add row to map.
-        map.push(line.chars().collect());
-    }
-
-    println!("Start: {:?}", start_position);
-    println!("End: {:?}", end_position);
-
-    (map, start_position, end_position)
-}
-
-fn find_valid_adjacent_paths(
-    map: &Vec<Vec<char>>,
-    tail_position: &(i8, i8, char),
-) -> Vec<(i8, i8, char)> {
-    let possible_positions = vec![
-        (tail_position.0, tail_position.1 - 1), // Left.
-        (tail_position.0, tail_position.1 + 1), // Right.
-        (tail_position.0 - 1, tail_position.1), // Up.
-        (tail_position.0 + 1, tail_position.1), // Down.
-    ];
-
-    let mut valid_adjacent_paths: Vec<(i8, i8, char)> = Vec::new();
-    for position in possible_positions.iter() {
-        // Validate bounds.
-        if position.0 >= 0
-            && position.0 < map.len() as i8
-            && position.1 >= 0
-            && position.1 < map[0].len() as i8
-        {
-            let character = map[position.0 as usize][position.1 as usize];
-            // Treat 'S' and 'E' as 'a' and 'z' respectively.
-            let destination = if character == 'S' { 'a' } else { character } as i32;
-            let current = if tail_position.2 == 'E' {
-                'z'
-            } else {
-                tail_position.2
-            } as i32;
-            // Validate you do not climb more than 1 height unit at a time.
-            // Given that we go backwards from goal, this means descend height not more than -1.
-            if destination - current >= -1 {
-                valid_adjacent_paths.push((position.0, position.1, character))
-            };
+    }
+
+    fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Parses one row per line, converting every character with `f`.
+    fn parse(input: &str, f: impl Fn(char) -> T) -> Self {
+        let mut width = 0;
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let row_start = cells.len();
+            cells.extend(line.chars().map(&f));
+            width = cells.len() - row_start;
+            height += 1;
         }
+        Grid::new(width, height, cells)
+    }
+
+    /// Iterates over the rows from top to bottom, each as a slice of its cells.
+    fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Iterates over all positions in row-major order.
+    fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)))
+    }
+
+    /// Returns the position of the first cell matching `predicate`, in row-major order.
+    fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        let index = self.cells.iter().position(predicate)?;
+        Some(Point::new(index / self.width, index % self.width))
+    }
+
+    /// Returns the up to four orthogonal neighbours of `point` that lie within the grid.
+    fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> {
+        let (height, width) = (self.height, self.width);
+        [
+            point
+                .row
+                .checked_sub(1)
+                .map(|row| Point::new(row, point.column)),
+            Some(Point::new(point.row + 1, point.column)).filter(|p| p.row < height),
+            point
+                .column
+                .checked_sub(1)
+                .map(|column| Point::new(point.row, column)),
+            Some(Point::new(point.row, point.column + 1)).filter(|p| p.column < width),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl<T> std::ops::Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        assert!(
+            row < self.height && column < self.width,
+            "Position out of bounds."
+        );
+        &self.cells[row * self.width + column]
+    }
+}
+
+impl<T> std::ops::IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.height && column < self.width,
+            "Position out of bounds."
+        );
+        &mut self.cells[row * self.width + column]
+    }
+}
+
+/// A position in a `Grid`, counted from the top-left corner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Point {
+    row: usize,
+    column: usize,
+}
+
+impl Point {
+    const fn new(row: usize, column: usize) -> Self {
+        Point { row, column }
+    }
+}
+
+impl<T> std::ops::Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        &self[(point.row, point.column)]
+    }
+}
+
+impl<T> std::ops::IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        &mut self[(point.row, point.column)]
+    }
+}
+
+struct HeightMap {
+    map: Grid<char>,
+    start: Point,
+    end: Point,
+}
+
+fn parse_data(contents: &str) -> HeightMap {
+    let map = Grid::parse(contents, |c| c);
+    let start = map.find(|&c| c == 'S').expect("Map should have a start.");
+    let end = map.find(|&c| c == 'E').expect("Map should have an end.");
+    HeightMap { map, start, end }
+}
+
+fn elevation(character: char) -> u8 {
+    // Treat 'S' and 'E' as 'a' and 'z' respectively.
+    match character {
+        'S' => b'a',
+        'E' => b'z',
+        _ => character as u8,
     }
-    valid_adjacent_paths
-}
-
-fn find_path(
-    map: &Vec<Vec<char>>,
-    start_position: &(i8, i8),
-    start_char: char,
-    end_char: char,
-) -> Vec<(i8, i8, char)> {
-    // Keep track of paths under consideration: this holds Dijkstra's shortest path tree.
-    let mut paths: Vec<Vec<(i8, i8, char)>> = Vec::new();
-
-    // Insert start position.
-    let start_position_with_char = (start_position.0, start_position.1, start_char);
-    let start_path = vec![start_position_with_char];
-    paths.push(start_path);
-
-    // Keep track of visited positions: the seen set.
-    let mut visited_positions: HashSet<(i8, i8, char)> = HashSet::new();
-    visited_positions.insert(start_position_with_char);
-
-    // Implement a modified shortest path algorithm.
-    loop {
-        let mut updated_paths: Vec<Vec<(i8, i8, char)>> = Vec::new();
-        for path in &paths {
-            // Find current tail position of the path.
-            let tail_position = path[path.len() - 1];
-            let next_positions = find_valid_adjacent_paths(&map, &tail_position);
-            for next_position in next_positions {
-                // Do not go back to earlier visited positions. NOTE: This check also avoid circles.
-                if visited_positions.contains(&next_position) {
-                    continue;
-                }
-                // Add next position to visited positions.
-                visited_positions.insert(next_position);
-
-                // Create new path until next position. To be added to the tree.
-                let mut updated_path = path.clone();
-                updated_path.push(next_position);
-                
-                // End looping once a path until end_char has been found.
-                if next_position.2 == end_char {
-                    return updated_path;
-                }
-                
-                // If not end_char, add the path to the list of paths that will be explored further.
-                updated_paths.push(updated_path);
+}
+
+fn can_climb(map: &Grid<char>, from: Point, to: Point) -> bool {
+    // You can descend any height, but climb no more than 1 height unit at a time.
+    elevation(map[to]) <= elevation(map[from]) + 1
+}
+
+/// Compute the number of steps from every position to `E`, with a single BFS over reversed edges.
+/// Positions that cannot reach `E` have no distance.
+fn compute_distances(height_map: &HeightMap) -> Grid<Option<u32>> {
+    let map = &height_map.map;
+    let mut distances: Grid<Option<u32>> = Grid::filled(map.width, map.height, None);
+    distances[height_map.end] = Some(0);
+
+    let mut queue: VecDeque<(Point, u32)> = VecDeque::from([(height_map.end, 0)]);
+    while let Some((position, distance)) = queue.pop_front() {
+        for previous in map.neighbours(position) {
+            // Walking backwards, so the step is from `previous` to `position`.
+            if distances[previous].is_none() && can_climb(map, previous, position) {
+                distances[previous] = Some(distance + 1);
+                queue.push_back((previous, distance + 1));
             }
         }
-        if updated_paths.len() == 0 {
-            println!("WARNING: Impossible to find path from start to finish!");
-            return vec![];
-        }
-        paths = updated_paths;
     }
+
+    distances
+}
+
+/// Follow decreasing distances from `start` to `E`.
+fn find_path(height_map: &HeightMap, distances: &Grid<Option<u32>>, start: Point) -> Vec<Point> {
+    let map = &height_map.map;
+    let mut path = vec![start];
+    let mut position = start;
+    while let Some(distance) = distances[position].filter(|&distance| distance > 0) {
+        position = map
+            .neighbours(position)
+            .find(|&next| distances[next] == Some(distance - 1) && can_climb(map, position, next))
+            .expect("A shorter distance should have a neighbour closer to the end.");
+        path.push(position);
+    }
+    path
+}
+
+fn terrain_color(character: char) -> Rgb {
+    // From dark green valleys to white peaks. Anything outside 'a'..='z' gets the nearest colour.
+    let level = elevation(character).clamp(b'a', b'z') - b'a';
+    [30 + 8 * level, 80 + 6 * level, 30 + 8 * level]
 }
 
-fn visualize_solution(map: &Vec<Vec<char>>, path: Vec<(i8, i8)>) {
-    for row_index in 0..map.len() {
-        let mut print_line: String = String::new();
-        for column_index in 0..map[0].len() {
-            if path.contains(&(row_index as i8, column_index as i8)) {
-                let character = map[row_index][column_index];
-                print_line.push(character);
+/// Prints the path in the terminal and writes it to `visualizations/{name}.png`, over the terrain.
+fn visualize_solution(height_map: &HeightMap, path: &[Point], name: &str) -> Result<(), String> {
+    let mut canvas: Grid<char> = Grid::filled(height_map.map.width, height_map.map.height, ' ');
+    for step in path.windows(2) {
+        let (from, to) = (step[0], step[1]);
+        canvas[from] = if to.row < from.row {
+            '^'
+        } else if to.row > from.row {
+            'v'
+        } else if to.column < from.column {
+            '<'
+        } else {
+            '>'
+        };
+    }
+    canvas[height_map.end] = 'E';
+
+    let cells = Grid::new(
+        canvas.width,
+        canvas.height,
+        height_map
+            .map
+            .cells
+            .iter()
+            .copied()
+            .zip(canvas.cells.iter().copied())
+            .collect(),
+    );
+    let on_path = [200, 30, 30];
+    print!(
+        "{}",
+        render_ansi(&cells, |&(terrain, step)| {
+            let color = if step == ' ' {
+                terrain_color(terrain)
             } else {
-                print_line.push('.');
+                on_path
+            };
+            (color, step)
+        })
+    );
+    write_png(
+        Path::new(&format!("visualizations/{name}.png")),
+        &cells,
+        4,
+        |&(terrain, step)| {
+            if step == ' ' {
+                terrain_color(terrain)
+            } else {
+                on_path
             }
-        }
-        println!("{}", print_line);
+        },
+    )
+}
+
+/// Animates the search from `E` to `visualizations/frontier.gif`, coloring reached positions.
+fn visualize_frontier(height_map: &HeightMap, distances: &Grid<Option<u32>>) -> Result<(), String> {
+    let map = &height_map.map;
+    let cells = Grid::new(
+        map.width,
+        map.height,
+        map.cells
+            .iter()
+            .copied()
+            .zip(distances.cells.iter().copied())
+            .collect(),
+    );
+    let farthest = distances.cells.iter().flatten().max().copied().unwrap_or(0);
+    let step = farthest.div_ceil(FRONTIER_FRAMES).max(1);
+    let mut recorder = GifRecorder::create(
+        Path::new("visualizations/frontier.gif"),
+        map.width,
+        map.height,
+        4,
+        5,
+    )?;
+    for reached in (0..=farthest + step).step_by(step as usize) {
+        recorder.add_frame(&cells, |&(terrain, distance)| match distance {
+            // The frontier itself, and everything reached before it.
+            Some(distance) if distance <= reached && reached - distance < step => [250, 220, 60],
+            Some(distance) if distance <= reached => [60, 110, 200],
+            _ => terrain_color(terrain),
+        })?;
     }
+    Ok(())
 }
 
-fn main() {
-    // Initialize problem.
-    let contents: String;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            contents = read_file("data/test.txt");
+fn solve(height_map: &HeightMap) -> (Option<u32>, Option<u32>) {
+    let distances = compute_distances(height_map);
+
+    // Part 1 starts at 'S'.
+    let part_1 = distances[height_map.start];
+    if visualize() && part_1.is_some() {
+        let path = find_path(height_map, &distances, height_map.start);
+        if let Err(error) = visualize_solution(height_map, &path, "part_1") {
+            println!("WARNING: {}", error);
         }
-        MODE::Real => {
-            // Read input file.
-            contents = read_file("data/input.txt");
+        if let Err(error) = visualize_frontier(height_map, &distances) {
+            println!("WARNING: {}", error);
         }
     }
 
+    // Part 2 starts at the closest position of lowest elevation.
+    let best_start = height_map
+        .map
+        .positions()
+        .map(|(row, column)| Point::new(row, column))
+        .filter(|&position| elevation(height_map.map[position]) == b'a')
+        .filter_map(|position| distances[position].map(|distance| (distance, position)))
+        .min();
+    if visualize() {
+        if let Some((_, position)) = best_start {
+            let path = find_path(height_map, &distances, position);
+            if let Err(error) = visualize_solution(height_map, &path, "part_2") {
+                println!("WARNING: {}", error);
+            }
+        }
+    }
+
+    (part_1, best_start.map(|(distance, _)| distance))
+}
+
+fn main() {
+    // Initialize problem.
+    let contents: String = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => read_file("data/test.txt"),
+        // Read input file.
+        Mode::Real => read_file("data/input.txt"),
+    };
+
     // Parse content into useful data.
-    let (map, _, end_position) = parse_data(&contents);
+    let height_map = parse_data(&contents);
+    let (part_1_score, part_2_score) = solve(&height_map);
+
+    println!("Mode: {:?}", CHOSEN_MODE);
 
     // Part 1.
-    // Swap start and end to search from the back.
-    let part_1_path = find_path(&map, &end_position, 'E', 'S');
-    if part_1_path.len() > 0 {
-        println!("Part 1 - Solution: {}", part_1_path.len() - 1);
-        // Visualize output.
-        let part_1_clean_path: Vec<(i8, i8)> = part_1_path.iter().map(|x| (x.0, x.1)).collect();
-        visualize_solution(&map, part_1_clean_path);
+    match part_1_score {
+        Some(score) => println!("Part 1 - Solution: {}", score),
+        None => println!("WARNING: Impossible to find path from start to finish!"),
     }
 
     // Part 2.
-    // Swap start and end to search from the back.
-    let part_2_path = find_path(&map, &end_position, 'E', 'a');
-    if part_2_path.len() > 0 {
-        println!("Part 2 - Solution: {}", part_2_path.len() - 1);
-        // Visualize output.
-        let part_2_clean_path: Vec<(i8, i8)> = part_2_path.iter().map(|x| (x.0, x.1)).collect();
-        visualize_solution(&map, part_2_clean_path);
+    match part_2_score {
+        Some(score) => println!("Part 2 - Solution: {}", score),
+        None => println!("WARNING: Impossible to find path from any lowest point to finish!"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi\n";
+
+    #[test]
+    fn test_solve() {
+        assert_eq!((Some(31), Some(29)), solve(&parse_data(TEST_INPUT)));
+    }
+
+    #[test]
+    fn test_wide_map() {
+        // Wider than an `i8` coordinate can address.
+        let contents = format!("S{}bcdefghijklmnopqrstuvwxyE\n", "a".repeat(300));
+        let height_map = parse_data(&contents);
+        assert_eq!((Some(325), Some(25)), solve(&height_map));
+        let distances = compute_distances(&height_map);
+        assert_eq!(
+            326,
+            find_path(&height_map, &distances, height_map.start).len()
+        );
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let grid = Grid::parse("ab\n", |c| c);
+        assert_eq!(
+            "\x1b[48;2;30;80;30mS\x1b[48;2;38;86;38mb\x1b[0m\n",
+            render_ansi(&grid, |&c| (
+                terrain_color(c),
+                if c == 'a' { 'S' } else { c }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_terrain_color_outside_the_alphabet() {
+        assert_eq!(terrain_color('a'), terrain_color('#'));
+        assert_eq!(terrain_color('z'), terrain_color('~'));
+        assert_eq!([230, 230, 230], terrain_color('E'));
     }
 }
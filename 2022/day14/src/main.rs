@@ -1,13 +1,50 @@
+mod render;
+mod tui;
+
+use render::{render_ansi, visualize, write_png, GifRecorder, Rgb};
 use std::cmp;
+use std::path::Path;
+use tui::{interactive, run_interactive, Steppable};
 
 // Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
+const CHOSEN_MODE: Mode = Mode::Real;
 #[allow(dead_code)]
-enum MODE {
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
+// Number of sand units coming to rest between frames of the animation.
+const SAND_PER_FRAME: u32 = 10;
+
+/// A rectangular grid stored row-major in a single vector, indexed by `(row, column)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "Grid should have width * height cells."
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct Point {
     x: i32,
@@ -39,7 +76,7 @@ fn parse_input(input: &str) -> Vec<Vec<Point>> {
     parsed_input
 }
 
-fn find_grid_dimensions(data: &Vec<Vec<Point>>, start_point: &Point) -> (i32, i32, i32, i32) {
+fn find_grid_dimensions(data: &[Vec<Point>], start_point: &Point) -> (i32, i32, i32, i32) {
     let mut min_x: i32 = i32::MAX;
     let mut max_x: i32 = 0;
     let mut min_y: i32 = i32::MAX;
@@ -84,17 +121,11 @@ fn create_grid(
     max_x: &i32,
     min_y: &i32,
     max_y: &i32,
-    data: &Vec<Vec<Point>>,
+    data: &[Vec<Point>],
 ) -> Vec<Vec<char>> {
     // Create grid filled with air (`.`).
-    let mut grid: Vec<Vec<char>> = Vec::new();
-    let mut row: Vec<char> = Vec::new();
-    for _ in *min_x..(max_x + 1) {
-        row.push('.');
-    }
-    for _ in *min_y..(max_y + 1) {
-        grid.push(row.clone());
-    }
+    let row: Vec<char> = vec!['.'; (max_x - min_x + 1) as usize];
+    let mut grid: Vec<Vec<char>> = vec![row; (max_y - min_y + 1) as usize];
 
     // Add source.
     let (normalized_source_x, normalized_source_y) = normalize_point(start_point, min_x, min_y);
@@ -115,7 +146,7 @@ fn create_grid(
                     if y_distance > 0 {
                         // Move down.
                         for y in previous_point.y..point.y + 1 {
-                            let next_point: Point = Point { x: point.x, y: y };
+                            let next_point: Point = Point { x: point.x, y };
                             let (normalized_x, normalized_y) =
                                 normalize_point(&next_point, min_x, min_y);
                             grid[normalized_y][normalized_x] = '#';
@@ -124,7 +155,7 @@ fn create_grid(
                     if y_distance < 0 {
                         // Move up.
                         for y in point.y..previous_point.y + 1 {
-                            let next_point: Point = Point { x: point.x, y: y };
+                            let next_point: Point = Point { x: point.x, y };
                             let (normalized_x, normalized_y) =
                                 normalize_point(&next_point, min_x, min_y);
                             grid[normalized_y][normalized_x] = '#';
@@ -136,7 +167,7 @@ fn create_grid(
                     if x_distance > 0 {
                         // Move right.
                         for x in previous_point.x..point.x + 1 {
-                            let next_point: Point = Point { x: x, y: point.y };
+                            let next_point: Point = Point { x, y: point.y };
                             let (normalized_x, normalized_y) =
                                 normalize_point(&next_point, min_x, min_y);
                             grid[normalized_y][normalized_x] = '#';
@@ -145,7 +176,7 @@ fn create_grid(
                     if x_distance < 0 {
                         // Move left.
                         for x in point.x..previous_point.x + 1 {
-                            let next_point: Point = Point { x: x, y: point.y };
+                            let next_point: Point = Point { x, y: point.y };
                             let (normalized_x, normalized_y) =
                                 normalize_point(&next_point, min_x, min_y);
                             grid[normalized_y][normalized_x] = '#';
@@ -159,30 +190,84 @@ fn create_grid(
     grid
 }
 
-fn visualize_grid(grid: &Vec<Vec<char>>, min_x: &i32) {
-    let mut top_line_string: String = String::new();
-    top_line_string.push_str("    ");
-    for i in 0..grid[0].len() {
-        top_line_string.push_str(&((i as i32 + min_x) % 10).to_string());
+fn cell_color(cell: &char) -> Rgb {
+    match cell {
+        '#' => [120, 110, 100],
+        'o' => [230, 190, 90],
+        '+' => [220, 60, 40],
+        _ => [20, 24, 40],
     }
-    println!("{}", top_line_string);
+}
+
+fn to_grid(grid: &[Vec<char>]) -> Grid<char> {
+    Grid::new(grid[0].len(), grid.len(), grid.concat())
+}
+
+/// Prints the cave in the terminal and writes it to `visualizations/{name}.png`.
+fn visualize_grid(grid: &[Vec<char>], name: &str) -> Result<(), String> {
+    let grid = to_grid(grid);
+    print!("{}", render_ansi(&grid, |cell| (cell_color(cell), *cell)));
+    write_png(
+        Path::new(&format!("visualizations/{name}.png")),
+        &grid,
+        3,
+        cell_color,
+    )
+}
+
+/// Where a unit of sand ends up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Fall {
+    Rest,
+    // The sand would fall outside of the grid (part 1).
+    OutOfBottom,
+    // The sand comes to rest on the source, blocking it (part 2).
+    Blocked,
+}
 
-    for (i, row) in grid.iter().enumerate() {
-        let mut print_string: String = String::new();
-        print_string.push_str(&format!("{:03} ", i));
-        for character in row {
-            print_string.push(*character);
+/// Drops one unit of sand from `(x, y)` and marks where it comes to rest.
+fn drop_sand(grid: &mut [Vec<char>], (mut x, mut y): (usize, usize), part: u8) -> Fall {
+    // Sand drops one unit at a time.
+    loop {
+        // Fall down if possible.
+        if y + 1 < grid.len() && grid[y + 1][x] == '.' {
+            y += 1;
+        }
+        // Else, fall down-left if possible.
+        else if y + 1 < grid.len() && x > 0 && grid[y + 1][x - 1] == '.' {
+            y += 1;
+            x -= 1;
+        }
+        // Else, fall down-right.
+        else if y + 1 < grid.len() && x + 1 < grid[0].len() && grid[y + 1][x + 1] == '.' {
+            y += 1;
+            x += 1;
+        }
+        // Stop if a sand particle would fall outside of the grid.
+        else if part == 1 && (x == 0 || x == grid[0].len() - 1 || y == grid.len() - 1) {
+            return Fall::OutOfBottom;
+        } else if part == 2 && grid[y][x] == '+' {
+            return Fall::Blocked;
+        }
+        // Else, rest.
+        else if grid[y][x] == '.' {
+            grid[y][x] = 'o';
+            return Fall::Rest;
+        } else {
+            println!("WARNING: This should not happen!");
         }
-        println!("{}", print_string);
     }
 }
 
+/// Drops sand until it flows out of the bottom (part 1) or blocks the source (part 2), and
+/// returns the units of sand that came to rest. Every few units, the cave is added to `recorder`.
 fn solve(
-    grid: &mut Vec<Vec<char>>,
+    grid: &mut [Vec<char>],
     start_point: &Point,
     min_x: &i32,
     min_y: &i32,
     part: u8,
+    mut recorder: Option<&mut GifRecorder>,
 ) -> u32 {
     // Keep track of the solution.
     let mut solution = 0;
@@ -190,57 +275,82 @@ fn solve(
     // Loop over sand particles until it flows out of the bottom.
     loop {
         // Create a new normalized sand particle.
-        let (mut x, mut y) = normalize_point(&start_point, min_x, min_y);
-
-        // Sand drops one unit at a time.
-        loop {
-            // Fall down if possible.
-            if y + 1 < grid.len() && grid[y + 1][x] == '.' {
-                y += 1;
-            }
-            // Else, fall down-left if possible.
-            else if y + 1 < grid.len() && x > 0 && grid[y + 1][x - 1] == '.' {
-                y += 1;
-                x -= 1;
-            }
-            // Else, fall down-right.
-            else if y + 1 < grid.len() && x + 1 < grid[0].len() && grid[y + 1][x + 1] == '.' {
-                y += 1;
-                x += 1;
-            }
-            // Stop if a sand particle would fall outside of the grid.
-            else if part == 1 && (x == 0 || x == grid[0].len() - 1 || y == grid.len() - 1) {
-                visualize_grid(&grid, &min_x);
-                return solution;
-            } else if part == 2 && grid[y][x] == '+' {
-                // Add one to block the source entirely.
-                return solution + 1;
-            }
-            // Else, rest.
-            else if grid[y][x] == '.' {
-                grid[y][x] = 'o';
-                break;
-            } else {
-                println!("WARNING: This should not happen!");
-            }
+        match drop_sand(grid, normalize_point(start_point, min_x, min_y), part) {
+            Fall::Rest => {}
+            Fall::OutOfBottom => return solution,
+            // Add one to block the source entirely.
+            Fall::Blocked => return solution + 1,
         }
         solution += 1;
+        if let Some(recorder) = recorder.as_mut().filter(|_| solution % SAND_PER_FRAME == 0) {
+            if let Err(error) = recorder.add_frame(&to_grid(grid), cell_color) {
+                println!("WARNING: {}", error);
+            }
+        }
     }
 }
 
-fn main() {
-    // Initialize problem.
-    let input: &str;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            input = include_str!("../data/test.txt");
+/// A cave filling with sand one unit at a time, to step through in the terminal.
+#[derive(Clone)]
+struct Cave {
+    grid: Vec<Vec<char>>,
+    source: (usize, usize),
+    part: u8,
+    sand: u32,
+    outcome: Option<Fall>,
+}
+
+impl Cave {
+    fn new(grid: Vec<Vec<char>>, start_point: &Point, min_x: &i32, min_y: &i32, part: u8) -> Cave {
+        Cave {
+            grid,
+            source: normalize_point(start_point, min_x, min_y),
+            part,
+            sand: 0,
+            outcome: None,
         }
-        MODE::Real => {
-            // Read input file.
-            input = include_str!("../data/input.txt");
+    }
+}
+
+impl Steppable for Cave {
+    fn step(&mut self) -> bool {
+        if self.outcome.is_some() {
+            return false;
         }
+        match drop_sand(&mut self.grid, self.source, self.part) {
+            Fall::Rest => self.sand += 1,
+            Fall::OutOfBottom => self.outcome = Some(Fall::OutOfBottom),
+            Fall::Blocked => {
+                self.sand += 1;
+                self.outcome = Some(Fall::Blocked);
+            }
+        }
+        true
+    }
+
+    fn inspect(&self) -> Vec<String> {
+        let status = match self.outcome {
+            None => "falling",
+            Some(Fall::OutOfBottom) => "flowing out of the bottom",
+            Some(_) => "blocking the source",
+        };
+        let mut lines = vec![
+            format!("Sand at rest: {} ({status})", self.sand),
+            String::new(),
+        ];
+        lines.extend(self.grid.iter().map(|row| row.iter().collect::<String>()));
+        lines
     }
+}
+
+fn main() {
+    // Initialize problem.
+    let input: &str = match CHOSEN_MODE {
+        // Read test file.
+        Mode::Test => include_str!("../data/test.txt"),
+        // Read input file.
+        Mode::Real => include_str!("../data/input.txt"),
+    };
 
     let data: Vec<Vec<Point>> = parse_input(input);
 
@@ -249,8 +359,43 @@ fn main() {
     let (min_x, max_x, min_y, max_y) = find_grid_dimensions(&data, &start_point);
     let grid: Vec<Vec<char>> = create_grid(&start_point, &min_x, &max_x, &min_y, &max_y, &data);
 
-    // Part 1.
-    let part_1_score = solve(&mut grid.clone(), &start_point, &min_x, &min_y, 1);
+    // Step through part 1 instead of solving.
+    if interactive() {
+        let cave = Cave::new(grid, &start_point, &min_x, &min_y, 1);
+        run_interactive("Falling sand", cave).expect("Terminal UI failed.");
+        return;
+    }
+
+    println!("Mode: {:?}", CHOSEN_MODE);
+
+    // Part 1, animated when visualizing.
+    let mut recorder = if visualize() {
+        GifRecorder::create(
+            Path::new("visualizations/sand.gif"),
+            grid[0].len(),
+            grid.len(),
+            3,
+            4,
+        )
+        .map_err(|error| println!("WARNING: {}", error))
+        .ok()
+    } else {
+        None
+    };
+    let mut part_1_grid = grid.clone();
+    let part_1_score = solve(
+        &mut part_1_grid,
+        &start_point,
+        &min_x,
+        &min_y,
+        1,
+        recorder.as_mut(),
+    );
+    if visualize() {
+        if let Err(error) = visualize_grid(&part_1_grid, "part_1") {
+            println!("WARNING: {}", error);
+        }
+    }
     println!("Part 1 - Solution: {}", part_1_score);
 
     // Part 2.
@@ -272,10 +417,57 @@ fn main() {
     // Fill the floor with rocks.
     let bottom_row = grid.len() - 1;
     let row_length = grid[0].len();
-    for i in 0..row_length {
-        grid[bottom_row][i] = '#';
-    }
+    grid[bottom_row] = vec!['#'; row_length];
 
-    let part_2_score = solve(&mut grid.clone(), &start_point, &enlarged_min_x, &min_y, 2);
+    let part_2_score = solve(
+        &mut grid.clone(),
+        &start_point,
+        &enlarged_min_x,
+        &min_y,
+        2,
+        None,
+    );
     println!("Part 2 - Solution: {}", part_2_score);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::{assert_rewinds, Session};
+
+    const SOURCE: Point = Point { x: 500, y: 0 };
+
+    /// The example cave for part 1, and the smallest coordinates its grid is normalized by.
+    fn example_cave() -> (Cave, i32, i32) {
+        let data = parse_input(include_str!("../data/test.txt"));
+        let (min_x, max_x, min_y, max_y) = find_grid_dimensions(&data, &SOURCE);
+        let grid = create_grid(&SOURCE, &min_x, &max_x, &min_y, &max_y, &data);
+        (Cave::new(grid, &SOURCE, &min_x, &min_y, 1), min_x, min_y)
+    }
+
+    #[test]
+    fn test_cave_rewind() {
+        // Rewinding takes the sand back out of the cave, including the unit that fell out.
+        let (cave, _, _) = example_cave();
+        assert_eq!(25, assert_rewinds(Session::new(cave)));
+    }
+
+    #[test]
+    fn test_cave_steps() {
+        let (mut cave, min_x, min_y) = example_cave();
+        let mut solved = cave.grid.clone();
+        while cave.step() {}
+        assert_eq!(Some(Fall::OutOfBottom), cave.outcome);
+
+        // Stepping fills the cave exactly like solving does.
+        assert_eq!(
+            cave.sand,
+            solve(&mut solved, &SOURCE, &min_x, &min_y, 1, None)
+        );
+        assert_eq!(solved, cave.grid);
+        assert_eq!(
+            "Sand at rest: 24 (flowing out of the bottom)",
+            cave.inspect()[0]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::Grid;
+
+/// An RGB colour.
+pub type Rgb = [u8; 3];
+
+/// Pixels of a grid, `scale` by `scale` per cell, as RGB bytes row by row.
+fn rasterize<T>(grid: &Grid<T>, scale: usize, palette: &impl Fn(&T) -> Rgb) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(grid.width * grid.height * scale * scale * 3);
+    for row in grid.rows() {
+        let colors: Vec<Rgb> = row.iter().map(palette).collect();
+        for _ in 0..scale {
+            for color in &colors {
+                for _ in 0..scale {
+                    pixels.extend_from_slice(color);
+                }
+            }
+        }
+    }
+    pixels
+}
+
+fn create_file(path: &Path) -> Result<BufWriter<File>, String> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)
+            .map_err(|error| format!("Failed to create {}: {error}", directory.display()))?;
+    }
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|error| format!("Failed to create {}: {error}", path.display()))
+}
+
+/// Writes a grid to a PNG image, with `palette` choosing the colour of every cell.
+pub fn write_png<T>(
+    path: &Path,
+    grid: &Grid<T>,
+    scale: usize,
+    palette: impl Fn(&T) -> Rgb,
+) -> Result<(), String> {
+    let mut encoder = png::Encoder::new(
+        create_file(path)?,
+        (grid.width * scale) as u32,
+        (grid.height * scale) as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&rasterize(grid, scale, &palette)))
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+/// Writes the frames of a simulation to an animated GIF, looping forever.
+pub struct GifRecorder {
+    encoder: gif::Encoder<BufWriter<File>>,
+    width: u16,
+    height: u16,
+    scale: usize,
+    // Time between frames, in hundredths of a second.
+    delay: u16,
+}
+
+impl GifRecorder {
+    pub fn create(
+        path: &Path,
+        width: usize,
+        height: usize,
+        scale: usize,
+        delay: u16,
+    ) -> Result<GifRecorder, String> {
+        let (width, height) = (
+            u16::try_from(width * scale).map_err(|_| "Frames are too wide for a GIF.")?,
+            u16::try_from(height * scale).map_err(|_| "Frames are too high for a GIF.")?,
+        );
+        let mut encoder = gif::Encoder::new(create_file(path)?, width, height, &[])
+            .map_err(|error| format!("Failed to start {}: {error}", path.display()))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|error| format!("Failed to start {}: {error}", path.display()))?;
+        Ok(GifRecorder {
+            encoder,
+            width,
+            height,
+            scale,
+            delay,
+        })
+    }
+
+    pub fn add_frame<T>(
+        &mut self,
+        grid: &Grid<T>,
+        palette: impl Fn(&T) -> Rgb,
+    ) -> Result<(), String> {
+        let pixels = rasterize(grid, self.scale, &palette);
+        if pixels.len() != self.width as usize * self.height as usize * 3 {
+            return Err("Frames should all have the same size.".to_string());
+        }
+        // Speed 10 quantizes the colours well enough for a handful of palette entries.
+        let mut frame = gif::Frame::from_rgb_speed(self.width, self.height, &pixels, 10);
+        frame.delay = self.delay;
+        self.encoder
+            .write_frame(&frame)
+            .map_err(|error| format!("Failed to write a frame: {error}"))
+    }
+}
+
+/// A grid as a frame of terminal output, with `palette` choosing the background colour and the
+/// character of every cell.
+pub fn render_ansi<T>(grid: &Grid<T>, palette: impl Fn(&T) -> (Rgb, char)) -> String {
+    let mut frame = String::new();
+    for row in grid.rows() {
+        for cell in row {
+            let ([red, green, blue], character) = palette(cell);
+            frame.push_str(&format!("\x1b[48;2;{red};{green};{blue}m{character}"));
+        }
+        frame.push_str("\x1b[0m\n");
+    }
+    frame
+}
+
+/// Whether the solver was started with `--visualize`, so rendering stays off by default.
+pub fn visualize() -> bool {
+    std::env::args().any(|argument| argument == "--visualize")
+}
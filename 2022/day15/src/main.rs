@@ -4,6 +4,7 @@ use std::collections::HashSet;
 // Choose the mode: Test or Real.
 const CHOSEN_MODE: MODE = MODE::Real;
 #[allow(dead_code)]
+#[derive(Debug)]
 enum MODE {
     Test,
     Real,
@@ -163,6 +164,8 @@ fn main() {
     // Parse input.
     let data: Vec<(Point, Point)> = parse_input(input);
 
+    println!("Mode: {:?}", CHOSEN_MODE);
+
     // Part 1.
     let part_1_score = solve_part_1(&data, solution_row);
     println!("Part 1 - Solution: {}", part_1_score);
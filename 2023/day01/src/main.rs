@@ -1,89 +1,274 @@
-// Choose the mode: Test or Real.
-const CHOSEN_MODE: MODE = MODE::Real;
-#[allow(dead_code)]
-enum MODE {
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+
+#[derive(Debug)]
+enum Mode {
     Test,
     Real,
 }
 
-const RADIX: u32 = 10;
+// Each part has its own example, the real input is shared.
+const TEST_FILES: [&str; 2] = ["test1", "test2"];
 
-fn reverse_string(string: &str) -> String {
-    string.chars().rev().collect()
+fn read_input(mode: &Mode, part: usize) -> std::io::Result<String> {
+    let file_name = match mode {
+        Mode::Test => TEST_FILES[part - 1],
+        Mode::Real => "input",
+    };
+    let file_path = format!("data/{file_name}.txt", file_name = file_name);
+    fs::read_to_string(&file_path)
 }
 
-fn find_number(input: &str) -> Option<(usize, u32)> {
-    input
-        .chars()
-        .enumerate()
-        .find_map(|(index, c)| c.to_digit(RADIX).map(|number| (index, number)))
+/// A match of a vocabulary pattern, as a byte range into the scanned string.
+#[derive(Debug, PartialEq, Eq)]
+struct Match<'a, T> {
+    start: usize,
+    end: usize,
+    value: &'a T,
 }
 
-fn replace_numbers(line: &str) -> String {
-    line.replace("one", "one1one")
-        .replace("two", "two2two")
-        .replace("three", "three3three")
-        .replace("four", "four4four")
-        .replace("five", "five5five")
-        .replace("six", "six6six")
-        .replace("seven", "seven7seven")
-        .replace("eight", "eight8eight")
-        .replace("nine", "nine9nine")
+/// An Aho–Corasick automaton over the bytes of a set of patterns.
+struct Automaton {
+    transitions: Vec<HashMap<u8, usize>>,
+    failure: Vec<usize>,
+    // Indices of all patterns ending in each state, including those reached via failure links.
+    outputs: Vec<Vec<usize>>,
 }
 
-fn solve_part_1(input: &str) -> u32 {
+impl Automaton {
+    fn new(patterns: &[Vec<u8>]) -> Self {
+        let mut automaton = Automaton {
+            transitions: vec![HashMap::new()],
+            failure: vec![0],
+            outputs: vec![Vec::new()],
+        };
+
+        // Build the trie.
+        for (index, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for &byte in pattern {
+                state = match automaton.transitions[state].get(&byte) {
+                    Some(&next) => next,
+                    None => {
+                        let next = automaton.transitions.len();
+                        automaton.transitions.push(HashMap::new());
+                        automaton.failure.push(0);
+                        automaton.outputs.push(Vec::new());
+                        automaton.transitions[state].insert(byte, next);
+                        next
+                    }
+                };
+            }
+            automaton.outputs[state].push(index);
+        }
+
+        // Compute failure links breadth-first, so shorter suffixes are always known.
+        let mut queue: VecDeque<usize> = automaton.transitions[0].values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let children: Vec<(u8, usize)> = automaton.transitions[state]
+                .iter()
+                .map(|(&byte, &child)| (byte, child))
+                .collect();
+            for (byte, child) in children {
+                let fallback = automaton.next_state(automaton.failure[state], byte);
+                automaton.failure[child] = fallback;
+                let inherited = automaton.outputs[fallback].clone();
+                automaton.outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        automaton
+    }
+
+    fn next_state(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.transitions[state].get(&byte) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.failure[state];
+        }
+    }
+}
+
+/// Finds every, possibly overlapping, occurrence of a configurable vocabulary of tokens.
+/// Scanning works from both ends, so the first and last token of a line are found without
+/// reversing or rewriting it.
+struct Scanner<T> {
+    vocabulary: Vec<(String, T)>,
+    forward: Automaton,
+    backward: Automaton,
+    max_length: usize,
+}
+
+impl<T> Scanner<T> {
+    fn new<S: Into<String>>(vocabulary: impl IntoIterator<Item = (S, T)>) -> Self {
+        let vocabulary: Vec<(String, T)> = vocabulary
+            .into_iter()
+            .map(|(pattern, value)| (pattern.into(), value))
+            .filter(|(pattern, _)| !pattern.is_empty())
+            .collect();
+        let patterns: Vec<Vec<u8>> = vocabulary
+            .iter()
+            .map(|(pattern, _)| pattern.bytes().collect())
+            .collect();
+        let reversed_patterns: Vec<Vec<u8>> = vocabulary
+            .iter()
+            .map(|(pattern, _)| pattern.bytes().rev().collect())
+            .collect();
+        Scanner {
+            max_length: patterns.iter().map(Vec::len).max().unwrap_or(0),
+            forward: Automaton::new(&patterns),
+            backward: Automaton::new(&reversed_patterns),
+            vocabulary,
+        }
+    }
+
+    fn to_match(&self, index: usize, start: usize) -> Match<'_, T> {
+        let (pattern, value) = &self.vocabulary[index];
+        Match {
+            start,
+            end: start + pattern.len(),
+            value,
+        }
+    }
+
+    /// Yields all matches from left to right, ordered by their end.
+    fn find_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match<'a, T>> + 'a {
+        let mut state = 0;
+        haystack
+            .bytes()
+            .enumerate()
+            .flat_map(move |(position, byte)| {
+                state = self.forward.next_state(state, byte);
+                self.forward.outputs[state].iter().map(move |&index| {
+                    self.to_match(index, position + 1 - self.vocabulary[index].0.len())
+                })
+            })
+    }
+
+    /// Yields all matches from right to left, ordered by their start.
+    fn rfind_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match<'a, T>> + 'a {
+        let mut state = 0;
+        haystack
+            .bytes()
+            .enumerate()
+            .rev()
+            .flat_map(move |(position, byte)| {
+                state = self.backward.next_state(state, byte);
+                self.backward.outputs[state]
+                    .iter()
+                    .map(move |&index| self.to_match(index, position))
+            })
+    }
+
+    /// Returns the match that starts first.
+    fn first<'a>(&'a self, haystack: &'a str) -> Option<Match<'a, T>> {
+        let mut best: Option<Match<T>> = None;
+        for candidate in self.find_iter(haystack) {
+            if let Some(best) = &best {
+                // Later matches end later, so they can no longer start before the best one.
+                if candidate.end >= best.start + self.max_length {
+                    break;
+                }
+            }
+            if best
+                .as_ref()
+                .is_none_or(|best| candidate.start < best.start)
+            {
+                best = Some(candidate);
+            }
+        }
+        best
+    }
+
+    /// Returns the match that starts last.
+    fn last<'a>(&'a self, haystack: &'a str) -> Option<Match<'a, T>> {
+        self.rfind_iter(haystack).next()
+    }
+}
+
+/// The vocabulary of the digits 1 to 9, optionally including their spelled-out names.
+fn digit_vocabulary(spelled: bool) -> Vec<(&'static str, u32)> {
+    const DIGITS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut vocabulary: Vec<(&'static str, u32)> = DIGITS.into_iter().zip(1..).collect();
+    if spelled {
+        vocabulary.extend(WORDS.into_iter().zip(1..));
+    }
+    vocabulary
+}
+
+fn calibration_value(scanner: &Scanner<u32>, line: &str) -> Option<u32> {
+    let first_digit = scanner.first(line)?.value;
+    let last_digit = scanner.last(line)?.value;
+    Some(first_digit * 10 + last_digit)
+}
+
+fn sum_calibration_values(input: &str, scanner: &Scanner<u32>) -> u32 {
     input
         .lines()
-        .filter_map(|line| {
-            let left_digit = find_number(line)?.1 * RADIX;
-            let right_digit = find_number(&reverse_string(line))?.1;
-            Some(left_digit + right_digit)
-        })
+        .filter_map(|line| calibration_value(scanner, line))
         .sum()
 }
 
+fn solve_part_1(input: &str) -> u32 {
+    sum_calibration_values(input, &Scanner::new(digit_vocabulary(false)))
+}
+
 fn solve_part_2(input: &str) -> u32 {
-    input
-        .lines()
-        .filter_map(|line| {
-            let replaced_line = replace_numbers(line);
-            let left_digit = find_number(&replaced_line)?.1 * RADIX;
-            let right_digit = find_number(&reverse_string(&replaced_line))?.1;
-            Some(left_digit + right_digit)
-        })
-        .sum()
+    sum_calibration_values(input, &Scanner::new(digit_vocabulary(true)))
 }
 
-fn main() {
-    // Initialize problem.
-    let mut input: &str;
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            input = include_str!("../data/test1.txt");
-        }
-        MODE::Real => {
-            // Read input file.
-            input = include_str!("../data/input.txt");
-        }
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let modes = [Mode::Test, Mode::Real];
+
+    for mode in modes.iter() {
+        println!("Mode: {:?}", mode);
+        let part_1_score = solve_part_1(&read_input(mode, 1)?);
+        println!("Part 1 - Solution: {score}", score = part_1_score);
+        let part_2_score = solve_part_2(&read_input(mode, 2)?);
+        println!("Part 2 - Solution: {score}", score = part_2_score);
     }
 
-    // Part 1.
-    let part_1_score = solve_part_1(input);
-    println!("Part 1 - Solution: {}", part_1_score);
+    Ok(())
+}
 
-    match CHOSEN_MODE {
-        MODE::Test => {
-            // Read test file.
-            input = include_str!("../data/test2.txt");
-        }
-        MODE::Real => {
-            // Read input file.
-            input = include_str!("../data/input.txt");
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_words() {
+        let scanner = Scanner::new(digit_vocabulary(true));
+        assert_eq!(Some(83), calibration_value(&scanner, "eighthree"));
+        assert_eq!(Some(79), calibration_value(&scanner, "sevenine"));
+        assert_eq!(Some(99), calibration_value(&scanner, "tgppgp9"));
+        assert_eq!(None, calibration_value(&scanner, "abc"));
     }
 
-    // Part 2.
-    let part_2_score = solve_part_2(input);
-    println!("Part 2 - Solution: {}", part_2_score);
+    #[test]
+    fn test_scanner_yields_all_matches() {
+        let scanner = Scanner::new(digit_vocabulary(true));
+        let positions = |matches: Vec<Match<u32>>| -> Vec<(usize, usize, u32)> {
+            matches.iter().map(|m| (m.start, m.end, *m.value)).collect()
+        };
+        let forward = positions(scanner.find_iter("xtwone3four").collect());
+        assert_eq!(vec![(1, 4, 2), (3, 6, 1), (6, 7, 3), (7, 11, 4)], forward);
+        let backward = positions(scanner.rfind_iter("xtwone3four").collect());
+        assert_eq!(vec![(7, 11, 4), (6, 7, 3), (3, 6, 1), (1, 4, 2)], backward);
+    }
+
+    #[test]
+    fn test_scanner_first_prefers_earliest_start() {
+        // "bc" ends before "abcd", but "abcd" starts earlier.
+        let scanner = Scanner::new([("abcd", 1), ("bc", 2)]);
+        assert_eq!(Some(&1), scanner.first("xabcd").map(|m| m.value));
+        assert_eq!(Some(&2), scanner.last("xabcd").map(|m| m.value));
+    }
 }
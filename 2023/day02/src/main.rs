@@ -1,73 +1,166 @@
-use std::{collections::HashMap, fs};
+use std::{env, fs, str::FromStr};
 
 #[derive(Debug)]
 enum Mode {
-    TEST,
-    REAL,
+    Test,
+    Real,
 }
 
-fn parse_line(line: &str) -> Vec<HashMap<&str, u32>> {
-    line.split_once(":")
-        .unwrap()
-        .1
-        .split(';')
-        .map(|part| {
-            part.split(',')
-                .map(|color_number_pair| {
-                    let color_number_pair = color_number_pair.trim();
-                    let (number, color) = color_number_pair.split_once(' ').unwrap();
-                    (color.trim(), number.trim().parse::<u32>().unwrap())
-                })
-                .collect::<HashMap<&str, u32>>()
-        })
-        .collect()
+// The bag from the puzzle, used unless the `BAG` environment variable provides another one.
+const DEFAULT_BAG: &str = "12 red, 13 green, 14 blue";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+impl Color {
+    const ALL: [Color; 3] = [Color::Red, Color::Green, Color::Blue];
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "red" => Ok(Color::Red),
+            "green" => Ok(Color::Green),
+            "blue" => Ok(Color::Blue),
+            _ => Err(format!("Unknown color: {s}")),
+        }
+    }
+}
+
+/// Number of cubes per color, used both for a single draw and for the contents of a bag.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Draw {
+    cubes: [u32; 3],
+}
+
+impl Draw {
+    fn count(&self, color: Color) -> u32 {
+        self.cubes[color as usize]
+    }
+
+    fn power(&self) -> u32 {
+        self.cubes.iter().product()
+    }
 }
 
-fn solve_part_1(input: &str) -> u32 {
-    let bag = HashMap::from([("red", 12), ("green", 13), ("blue", 14)]);
+impl FromStr for Draw {
+    type Err = String;
+
+    /// Parses a draw such as `3 blue, 4 red`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut draw = Draw::default();
+        for cubes in s.split(',') {
+            let (number, color) = cubes
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| format!("Invalid cubes: {cubes}"))?;
+            let number: u32 = number
+                .parse()
+                .map_err(|_| format!("Invalid number of cubes: {number}"))?;
+            draw.cubes[color.trim().parse::<Color>()? as usize] += number;
+        }
+        Ok(draw)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Game {
+    id: u32,
+    draws: Vec<Draw>,
+}
+
+impl Game {
+    /// The fewest cubes of each color that make every draw of the game possible.
+    fn minimal_bag(&self) -> Draw {
+        let mut bag = Draw::default();
+        for draw in &self.draws {
+            for color in Color::ALL {
+                let index = color as usize;
+                bag.cubes[index] = bag.cubes[index].max(draw.count(color));
+            }
+        }
+        bag
+    }
+
+    /// The colors of which some draw needs more cubes than the bag holds.
+    fn infeasible_colors(&self, bag: &Draw) -> Vec<Color> {
+        let minimal_bag = self.minimal_bag();
+        Color::ALL
+            .into_iter()
+            .filter(|&color| minimal_bag.count(color) > bag.count(color))
+            .collect()
+    }
+
+    fn is_feasible(&self, bag: &Draw) -> bool {
+        self.infeasible_colors(bag).is_empty()
+    }
+}
+
+impl FromStr for Game {
+    type Err = String;
+
+    /// Parses a game such as `Game 1: 3 blue, 4 red; 1 red, 2 green`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, draws) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Missing colon in game: {s}"))?;
+        let id = header
+            .strip_prefix("Game ")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(|| format!("Invalid game id: {header}"))?;
+        let draws = draws
+            .split(';')
+            .map(str::parse)
+            .collect::<Result<Vec<Draw>, String>>()?;
+        Ok(Game { id, draws })
+    }
+}
+
+fn parse_games(input: &str) -> Vec<Game> {
     input
         .lines()
-        .enumerate()
-        .filter_map(|(index, line)| {
-            parse_line(line)
-                .iter()
-                .all(|draw| draw.iter().all(|(color, number)| bag[color] >= *number))
-                .then(|| (index + 1) as u32)
-        })
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse().expect("Invalid game."))
+        .collect()
+}
+
+fn solve_part_1(input: &str, bag: &Draw) -> u32 {
+    parse_games(input)
+        .iter()
+        .filter(|game| game.is_feasible(bag))
+        .map(|game| game.id)
         .sum()
 }
 
 fn solve_part_2(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| {
-            let mut max_by_ball = HashMap::from([("red", 0), ("green", 0), ("blue", 0)]);
-            parse_line(line)
-                .iter()
-                .flatten()
-                .for_each(|(color, &number)| {
-                    max_by_ball
-                        .entry(color)
-                        .and_modify(|max_value: &mut u32| *max_value = (*max_value).max(number));
-                });
-            max_by_ball.values().product::<u32>()
-        })
+    parse_games(input)
+        .iter()
+        .map(|game| game.minimal_bag().power())
         .sum()
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let modes = [Mode::TEST, Mode::REAL];
+    let bag: Draw = env::var("BAG")
+        .unwrap_or_else(|_| DEFAULT_BAG.to_string())
+        .parse()?;
+    let modes = [Mode::Test, Mode::Real];
 
     for mode in modes.iter() {
         let file_name = match mode {
-            Mode::TEST => "test",
-            Mode::REAL => "input",
+            Mode::Test => "test",
+            Mode::Real => "input",
         };
         let file_path = format!("data/{}.txt", file_name);
         let input = fs::read_to_string(&file_path)?;
 
         println!("Mode: {:?}", mode);
-        let part_1_score = solve_part_1(&input);
+        let part_1_score = solve_part_1(&input, &bag);
         println!("Part 1 - Solution: {score}", score = part_1_score);
         let part_2_score = solve_part_2(&input);
         println!("Part 2 - Solution: {score}", score = part_2_score);
@@ -75,3 +168,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_game() {
+        let game: Game = "Game 42: 3 blue, 4 red; 1 red, 2 green".parse().unwrap();
+        assert_eq!(42, game.id);
+        assert_eq!(2, game.draws.len());
+        assert_eq!(4, game.draws[0].count(Color::Red));
+        assert!("Game 1: 3 purple".parse::<Game>().is_err());
+        assert!("Game x: 3 blue".parse::<Game>().is_err());
+    }
+
+    #[test]
+    fn test_bag_queries() {
+        let game: Game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green"
+            .parse()
+            .unwrap();
+        let bag: Draw = DEFAULT_BAG.parse().unwrap();
+        assert_eq!(vec![Color::Red], game.infeasible_colors(&bag));
+        assert!(!game.is_feasible(&bag));
+        assert!(game.is_feasible(&game.minimal_bag()));
+        assert_eq!(20 * 13 * 6, game.minimal_bag().power());
+    }
+}
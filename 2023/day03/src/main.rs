@@ -3,133 +3,127 @@ use std::{collections::HashMap, fs};
 
 #[derive(Debug)]
 enum Mode {
-    TEST,
-    REAL,
+    Test,
+    Real,
 }
 
 const RADIX: u32 = 10;
 
-fn to_2d_vector(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|row| row.chars().collect()).collect()
+/// A number in the schematic, spanning columns `start..end` of a single row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct NumberSpan {
+    value: u32,
+    row: usize,
+    start: usize,
+    end: usize,
 }
 
-fn find_adjacent_symbol(symbols: &Vec<Vec<bool>>, row_index: usize, column_index: usize) -> bool {
-    let row_min = row_index.saturating_sub(1);
-    let row_max = std::cmp::min(row_index + 1, symbols.len() - 1);
-    let col_min = column_index.saturating_sub(1);
-    let col_max = std::cmp::min(column_index + 1, symbols[0].len() - 1);
-
-    for r in row_min..=row_max {
-        for c in col_min..=col_max {
-            // Avoid checking the cell itself.
-            if r == row_index && c == column_index {
-                continue;
-            }
-            if symbols[r][c] {
-                return true;
-            }
-        }
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Symbol {
+    character: char,
+    row: usize,
+    column: usize,
+}
 
-    false
+/// All numbers and symbols of a schematic, with the adjacency between them in both directions.
+struct Schematic {
+    numbers: Vec<NumberSpan>,
+    symbols: Vec<Symbol>,
+    // Indices into `symbols` for every number, and into `numbers` for every symbol.
+    symbols_by_number: Vec<Vec<usize>>,
+    numbers_by_symbol: Vec<Vec<usize>>,
 }
 
-fn solve_part_1(input: &str) -> u32 {
-    let board: Vec<Vec<char>> = to_2d_vector(input);
-    let symbols: Vec<Vec<bool>> = board
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|&c| !(c.is_digit(RADIX) || c == '.'))
-                .collect()
-        })
-        .collect();
-
-    let re = Regex::new(r"\d+").unwrap();
-    let mut numbers_to_accumulate = Vec::new();
-    for (row_index, line) in input.lines().enumerate() {
-        for capture in re.find_iter(line) {
-            let start_column_index = capture.start();
-            for (character_index, _) in capture.as_str().chars().enumerate() {
-                let column_index = start_column_index + character_index;
-                if find_adjacent_symbol(&symbols, row_index, column_index) {
-                    numbers_to_accumulate.push(capture.as_str().parse::<u32>().unwrap());
-                    break;
+impl Schematic {
+    fn parse(input: &str) -> Schematic {
+        let number_regex = Regex::new(r"\d+").unwrap();
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        let mut symbol_positions: HashMap<(usize, usize), usize> = HashMap::new();
+
+        for (row, line) in input.lines().enumerate() {
+            for capture in number_regex.find_iter(line) {
+                numbers.push(NumberSpan {
+                    value: capture.as_str().parse().unwrap(),
+                    row,
+                    start: capture.start(),
+                    end: capture.end(),
+                });
+            }
+            for (column, character) in line.char_indices() {
+                if !(character.is_digit(RADIX) || character == '.') {
+                    symbol_positions.insert((row, column), symbols.len());
+                    symbols.push(Symbol {
+                        character,
+                        row,
+                        column,
+                    });
                 }
             }
         }
-    }
-    numbers_to_accumulate.iter().sum()
-}
 
-fn find_adjacent_gear(
-    matrix: &Vec<Vec<bool>>,
-    row_index: usize,
-    start_column_index: usize,
-    capture: &str,
-    parts: &mut HashMap<(usize, usize), Vec<u32>>,
-) {
-    let row_min = row_index.saturating_sub(1);
-    let row_max = std::cmp::min(row_index + 1, matrix.len() - 1);
-
-    for (character_index, _) in capture.chars().enumerate() {
-        let column_index = start_column_index + character_index;
-        let col_min = column_index.saturating_sub(1);
-        let col_max = std::cmp::min(column_index + 1, matrix[0].len() - 1);
-        for r in row_min..=row_max {
-            for c in col_min..=col_max {
-                // Avoid checking the cell itself.
-                if r == row_index && c == column_index {
-                    continue;
-                }
-                if matrix[r][c] {
-                    parts
-                        .entry((r, c))
-                        .or_insert_with(Vec::new)
-                        .push(capture.parse::<u32>().unwrap());
-                    return;
+        // Link every number to each symbol in its surrounding border, so a number touching
+        // several symbols is attached to all of them.
+        let mut symbols_by_number = vec![Vec::new(); numbers.len()];
+        let mut numbers_by_symbol = vec![Vec::new(); symbols.len()];
+        for (number_index, number) in numbers.iter().enumerate() {
+            for row in number.row.saturating_sub(1)..=number.row + 1 {
+                for column in number.start.saturating_sub(1)..=number.end {
+                    if let Some(&symbol_index) = symbol_positions.get(&(row, column)) {
+                        symbols_by_number[number_index].push(symbol_index);
+                        numbers_by_symbol[symbol_index].push(number_index);
+                    }
                 }
             }
         }
-    }
-}
 
-fn solve_part_2(input: &str) -> u32 {
-    let board: Vec<Vec<char>> = to_2d_vector(input);
-    let symbols: Vec<Vec<bool>> = board
-        .iter()
-        .map(|row| row.iter().map(|&c| c == '*').collect())
-        .collect();
-
-    let re = Regex::new(r"\d+").unwrap();
-    let mut parts: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
-    for (row_index, line) in input.lines().enumerate() {
-        for capture in re.find_iter(line) {
-            let start_column_index = capture.start();
-            find_adjacent_gear(
-                &symbols,
-                row_index,
-                start_column_index,
-                capture.as_str(),
-                &mut parts,
-            );
+        Schematic {
+            numbers,
+            symbols,
+            symbols_by_number,
+            numbers_by_symbol,
         }
     }
 
-    parts
-        .values()
-        .filter(|part| part.len() == 2)
-        .map(|part| part[0] * part[1])
+    /// Numbers adjacent to at least one symbol matching `predicate`, each reported once.
+    fn numbers_adjacent(&self, predicate: impl Fn(&Symbol) -> bool) -> Vec<&NumberSpan> {
+        self.numbers
+            .iter()
+            .zip(self.symbols_by_number.iter())
+            .filter(|(_, symbols)| symbols.iter().any(|&index| predicate(&self.symbols[index])))
+            .map(|(number, _)| number)
+            .collect()
+    }
+
+    /// Product of the two numbers of every `*` adjacent to exactly two numbers.
+    fn gear_ratios(&self) -> impl Iterator<Item = u32> + '_ {
+        self.symbols
+            .iter()
+            .zip(self.numbers_by_symbol.iter())
+            .filter(|(symbol, numbers)| symbol.character == '*' && numbers.len() == 2)
+            .map(|(_, numbers)| self.numbers[numbers[0]].value * self.numbers[numbers[1]].value)
+    }
+}
+
+fn solve_part_1(input: &str) -> u32 {
+    Schematic::parse(input)
+        .numbers_adjacent(|_| true)
+        .iter()
+        .map(|number| number.value)
         .sum()
 }
 
+fn solve_part_2(input: &str) -> u32 {
+    Schematic::parse(input).gear_ratios().sum()
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let modes = [Mode::TEST, Mode::REAL];
+    let modes = [Mode::Test, Mode::Real];
 
     for mode in modes.iter() {
         let file_name = match mode {
-            Mode::TEST => "test",
-            Mode::REAL => "input",
+            Mode::Test => "test",
+            Mode::Real => "input",
         };
         let file_path = format!("data/{file_name}.txt", file_name = file_name);
         let input = fs::read_to_string(&file_path)?;
@@ -143,3 +137,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_touching_two_gears() {
+        // 5 touches both stars, so each star is adjacent to exactly two numbers.
+        let schematic = Schematic::parse("2*5*3\n.....\n");
+        assert_eq!(vec![10, 15], schematic.gear_ratios().collect::<Vec<u32>>());
+        assert_eq!(2, schematic.symbols_by_number[1].len());
+    }
+
+    #[test]
+    fn test_numbers_adjacent_to() {
+        let schematic = Schematic::parse("467..114..\n...*......\n..35..633.\n......#...\n");
+        let values: Vec<u32> = schematic
+            .numbers_adjacent(|symbol| symbol.character == '#')
+            .iter()
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(vec![633], values);
+    }
+}
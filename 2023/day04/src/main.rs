@@ -1,79 +1,122 @@
-use std::{collections::HashMap, fs};
+use std::{fs, str::FromStr};
 
 #[derive(Debug)]
 enum Mode {
-    TEST,
-    REAL,
+    Test,
+    Real,
 }
 
-fn parse_line(line: &str) -> (Vec<u32>, Vec<u32>) {
-    let parts: Vec<Vec<u32>> = line
-        .split_once(":")
-        .unwrap()
-        .1
-        .split("|")
-        .map(|list| {
-            list.trim()
-                .split_whitespace()
-                .map(|number| number.parse::<u32>().unwrap())
-                .collect()
+// Print the number of copies of every card after solving.
+const REPORT_COPIES: bool = false;
+
+/// A scratchcard with its numbers stored as bitsets, so matching is a single `AND`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Scratchcard {
+    id: usize,
+    winning_numbers: u128,
+    numbers: u128,
+}
+
+impl Scratchcard {
+    fn matches(&self) -> u32 {
+        (self.winning_numbers & self.numbers).count_ones()
+    }
+
+    /// One point for the first match, doubled for every further match.
+    fn points(&self) -> Result<u32, String> {
+        match self.matches() {
+            0 => Ok(0),
+            matches => 2u32
+                .checked_pow(matches - 1)
+                .ok_or_else(|| format!("Card {} has too many points: {matches} matches", self.id)),
+        }
+    }
+}
+
+fn parse_numbers(list: &str) -> Result<u128, String> {
+    list.split_whitespace()
+        .try_fold(0, |set, number| match number.parse::<u32>() {
+            Ok(number) if number < u128::BITS => Ok(set | 1 << number),
+            _ => Err(format!("Number should be in 0..{}: {number}", u128::BITS)),
         })
-        .collect();
-    (
-        parts.get(0).cloned().unwrap(),
-        parts.get(1).cloned().unwrap(),
-    )
 }
 
-fn count_winning_numbers(winning_numbers: &Vec<u32>, numbers: &Vec<u32>) -> usize {
-    winning_numbers
-        .iter()
-        .filter(|&number| numbers.contains(number))
-        .count()
+impl FromStr for Scratchcard {
+    type Err = String;
+
+    /// Parses a card such as `Card 1: 41 48 83 | 83 86 6`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, lists) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Missing colon in card: {s}"))?;
+        let id = header
+            .strip_prefix("Card")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(|| format!("Invalid card id: {header}"))?;
+        let (winning_numbers, numbers) = lists
+            .split_once('|')
+            .ok_or_else(|| format!("Missing separator in card: {s}"))?;
+        Ok(Scratchcard {
+            id,
+            winning_numbers: parse_numbers(winning_numbers)?,
+            numbers: parse_numbers(numbers)?,
+        })
+    }
 }
 
-fn solve_part_1(input: &str) -> u32 {
+fn parse_cards(input: &str) -> Vec<Scratchcard> {
     input
         .lines()
-        .map(|line| parse_line(line))
-        .map(|(part1, part2)| {
-            let count = count_winning_numbers(&part1, &part2);
-            if count > 0 {
-                2u32.pow(count as u32 - 1)
-            } else {
-                0
-            }
-        })
-        .sum()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse().expect("Invalid card."))
+        .collect()
+}
+
+/// The number of copies of each card, in table order, once all won copies are processed.
+/// Copies that would cascade past the end of the table are not won.
+fn copy_counts(cards: &[Scratchcard]) -> Vec<usize> {
+    let mut copies = vec![1; cards.len()];
+    for (index, card) in cards.iter().enumerate() {
+        let last_won = (index + card.matches() as usize).min(cards.len() - 1);
+        for won in index + 1..=last_won {
+            copies[won] += copies[index];
+        }
+    }
+    copies
+}
+
+fn solve_part_1(input: &str) -> Result<u32, String> {
+    parse_cards(input).iter().try_fold(0u32, |total, card| {
+        total
+            .checked_add(card.points()?)
+            .ok_or_else(|| "Total points overflow.".to_string())
+    })
 }
 
 fn solve_part_2(input: &str) -> usize {
-    let mut cards = HashMap::new();
-    input.lines().enumerate().for_each(|(line_index, line)| {
-        let copies = cards.entry(line_index).or_insert(1);
-        let (part1, part2) = parse_line(line);
-        let count = count_winning_numbers(&part1, &part2);
-        let new_copies = copies.clone();
-        (1..=count).for_each(|copy_index| {
-            *cards.entry(line_index + copy_index).or_insert(1) += new_copies;
-        });
-    });
-    cards.values().sum()
+    let cards = parse_cards(input);
+    let copies = copy_counts(&cards);
+    if REPORT_COPIES {
+        for (card, copies) in cards.iter().zip(copies.iter()) {
+            println!("Card {}: {} copies", card.id, copies);
+        }
+    }
+    copies.iter().sum()
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let modes = [Mode::TEST, Mode::REAL];
+    let modes = [Mode::Test, Mode::Real];
 
     for mode in modes.iter() {
         let file_name = match mode {
-            Mode::TEST => "test",
-            Mode::REAL => "input",
+            Mode::Test => "test",
+            Mode::Real => "input",
         };
         let file_path = format!("data/{file_name}.txt", file_name = file_name);
         let input = fs::read_to_string(&file_path)?;
 
         println!("Mode: {:?}", mode);
-        let part_1_score = solve_part_1(&input);
+        let part_1_score = solve_part_1(&input)?;
         println!("Part 1 - Solution: {score}", score = part_1_score);
         let part_2_score = solve_part_2(&input);
         println!("Part 2 - Solution: {score}", score = part_2_score);
@@ -81,3 +124,43 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn test_copy_counts() {
+        let cards = parse_cards(TEST_INPUT);
+        assert_eq!(vec![1, 2, 4, 8, 14, 1], copy_counts(&cards));
+        assert_eq!(
+            vec![1, 2, 3, 4, 5, 6],
+            cards.iter().map(|c| c.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_points_overflow() {
+        assert_eq!(Ok(13), solve_part_1(TEST_INPUT));
+        let numbers: Vec<String> = (0..33).map(|number| number.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let card: Scratchcard = format!("Card 7: {numbers} | {numbers}").parse().unwrap();
+        assert_eq!(33, card.matches());
+        assert!(card.points().is_err());
+    }
+
+    #[test]
+    fn test_cascade_clamps_at_table_end() {
+        // The last card would win copies of two cards that do not exist.
+        let cards = parse_cards("Card 1: 1 | 1\nCard 2: 1 2 | 1 2\n");
+        assert_eq!(vec![1, 2], copy_counts(&cards));
+    }
+}
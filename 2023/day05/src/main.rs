@@ -2,121 +2,197 @@ use std::{collections::HashMap, fs};
 
 #[derive(Debug)]
 enum Mode {
-    TEST,
-    REAL,
+    Test,
+    Real,
 }
 
-const DATA_CATEGORIES: [&str; 7] = [
-    "seed-to-soil",
-    "soil-to-fertilizer",
-    "fertilizer-to-water",
-    "water-to-light",
-    "light-to-temperature",
-    "temperature-to-humidity",
-    "humidity-to-location",
-];
-
-fn read_seeds(file_name_prefix: &str) -> Vec<u64> {
-    let file_path = format!(
-        "data/{file_name_prefix}_seeds.txt",
-        file_name_prefix = file_name_prefix,
-    );
-    let input = fs::read_to_string(&file_path).expect("File not found.");
-    input
-        .lines()
-        .next()
-        .expect("No lines in file.")
-        .split_whitespace()
-        .map(|number| number.parse::<u64>().expect("Failed to parse number."))
-        .collect()
+/// Maps `source_start..source_start + length` onto `destination_start..`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct MapRange {
+    destination_start: u64,
+    source_start: u64,
+    length: u64,
 }
 
-fn read_categories(file_name_prefix: &str) -> HashMap<&str, Vec<(u64, u64, u64)>> {
-    let mut data_map: HashMap<&str, Vec<(u64, u64, u64)>> = HashMap::new();
-    DATA_CATEGORIES.iter().for_each(|category| {
-        let file_path = format!(
-            "data/{file_name_prefix}_{category}.txt",
-            file_name_prefix = file_name_prefix,
-            category = category
-        );
-        let input = fs::read_to_string(&file_path).unwrap();
-        let data: Vec<(u64, u64, u64)> = input
-            .lines()
-            .map(|line| {
-                let line_data = line
-                    .split_whitespace()
-                    .map(|number| number.parse::<u64>().unwrap())
-                    .collect::<Vec<u64>>();
-                (line_data[0], line_data[1], line_data[2])
+/// A `source-to-destination map:` section of the almanac.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CategoryMap {
+    source: String,
+    destination: String,
+    ranges: Vec<MapRange>,
+}
+
+impl CategoryMap {
+    fn convert(&self, value: u64) -> u64 {
+        self.ranges
+            .iter()
+            .find_map(|range| {
+                if range.source_start <= value && value < range.source_start + range.length {
+                    Some(range.destination_start + value - range.source_start)
+                } else {
+                    None
+                }
             })
-            .collect();
+            // Values outside every range map onto themselves.
+            .unwrap_or(value)
+    }
+}
 
-        data_map.insert(&category, data);
-    });
-    data_map
+struct Almanac {
+    seeds: Vec<u64>,
+    // Keyed by source category.
+    maps: HashMap<String, CategoryMap>,
 }
 
-fn find_location(seed: &u64, data_map: &HashMap<&str, Vec<(u64, u64, u64)>>) -> u64 {
-    DATA_CATEGORIES
-        .iter()
-        .fold(*seed, |current_key, &category| {
-            let category_data = data_map
-                .get(category)
-                .expect("Category not found in data_map.");
-            category_data
-                .iter()
-                .find_map(|&(destination_start, source_start, source_range)| {
-                    if source_start <= current_key && current_key < source_start + source_range {
-                        Some(destination_start + current_key - source_start)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or(current_key)
+fn parse_numbers(line: &str) -> Result<Vec<u64>, String> {
+    line.split_whitespace()
+        .map(|number| {
+            number
+                .parse::<u64>()
+                .map_err(|_| format!("Failed to parse number: {number}"))
         })
+        .collect()
 }
 
-fn solve_part_1(file_name_prefix: &str) -> u64 {
-    let seeds = read_seeds(file_name_prefix);
-    let data_map = read_categories(file_name_prefix);
-    seeds
+impl Almanac {
+    /// Parses the raw puzzle input: a `seeds:` line followed by map sections separated by empty
+    /// lines, in any order.
+    fn parse(input: &str) -> Result<Almanac, String> {
+        let mut sections = input
+            .split("\n\n")
+            .filter(|section| !section.trim().is_empty());
+        let seeds = sections
+            .next()
+            .and_then(|section| section.trim().strip_prefix("seeds:"))
+            .ok_or("Almanac should start with the seeds.")?;
+        let seeds = parse_numbers(seeds)?;
+
+        let mut maps = HashMap::new();
+        for section in sections {
+            let mut lines = section.lines();
+            let header = lines.next().unwrap_or_default();
+            let (source, destination) = header
+                .strip_suffix(" map:")
+                .and_then(|name| name.split_once("-to-"))
+                .ok_or_else(|| format!("Invalid map header: {header}"))?;
+            let ranges = lines
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| match parse_numbers(line)?[..] {
+                    [destination_start, source_start, length] => Ok(MapRange {
+                        destination_start,
+                        source_start,
+                        length,
+                    }),
+                    _ => Err(format!("Invalid map range: {line}")),
+                })
+                .collect::<Result<Vec<MapRange>, String>>()?;
+
+            let map = CategoryMap {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                ranges,
+            };
+            if maps.insert(map.source.clone(), map).is_some() {
+                return Err(format!("Duplicate map from category: {source}"));
+            }
+        }
+
+        Ok(Almanac { seeds, maps })
+    }
+
+    /// The maps leading from category `from` to category `to`, following their names.
+    fn chain(&self, from: &str, to: &str) -> Result<Vec<&CategoryMap>, String> {
+        let mut chain = Vec::new();
+        let mut category = from;
+        while category != to {
+            let map = self
+                .maps
+                .get(category)
+                .ok_or_else(|| format!("No map from category: {category}"))?;
+            // Every map can be used once, so a longer chain must contain a cycle.
+            if chain.len() == self.maps.len() {
+                return Err(format!("Maps from {from} never reach {to}."));
+            }
+            chain.push(map);
+            category = &map.destination;
+        }
+        Ok(chain)
+    }
+}
+
+fn find_location(seed: u64, chain: &[&CategoryMap]) -> u64 {
+    chain.iter().fold(seed, |value, map| map.convert(value))
+}
+
+fn solve_part_1(input: &str) -> u64 {
+    let almanac = Almanac::parse(input).expect("Invalid almanac.");
+    let chain = almanac.chain("seed", "location").expect("Invalid maps.");
+    almanac
+        .seeds
         .iter()
-        .map(|seed| find_location(seed, &data_map))
+        .map(|&seed| find_location(seed, &chain))
         .min()
         .expect("No minimum exists.")
 }
 
-fn solve_part_2(file_name_prefix: &str) -> u64 {
-    let seeds: Vec<(u64, u64)> = read_seeds(file_name_prefix)
+fn solve_part_2(input: &str) -> u64 {
+    let almanac = Almanac::parse(input).expect("Invalid almanac.");
+    let chain = almanac.chain("seed", "location").expect("Invalid maps.");
+    let seeds: Vec<(u64, u64)> = almanac
+        .seeds
         .chunks(2)
         .map(|chunk: &[u64]| (chunk[0], chunk[1]))
         .collect();
-    let data_map = read_categories(file_name_prefix);
 
     seeds
         .iter()
         .flat_map(|&(seed_start, seed_range)| {
-            (seed_start..seed_start + seed_range).map(|seed| find_location(&seed, &data_map))
+            (seed_start..seed_start + seed_range).map(|seed| find_location(seed, &chain))
         })
         .min()
         .expect("No minimum exists.")
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let modes = [Mode::TEST, Mode::REAL];
+    let modes = [Mode::Test, Mode::Real];
 
     for mode in modes.iter() {
-        let file_name_prefix = match mode {
-            Mode::TEST => "test",
-            Mode::REAL => "input",
+        let file_name = match mode {
+            Mode::Test => "test",
+            Mode::Real => "input",
         };
+        let file_path = format!("data/{file_name}.txt", file_name = file_name);
+        let input = fs::read_to_string(&file_path)?;
 
         println!("Mode: {:?}", mode);
-        let part_1_score = solve_part_1(file_name_prefix);
+        let part_1_score = solve_part_1(&input);
         println!("Part 1 - Solution: {score}", score = part_1_score);
-        let part_2_score = solve_part_2(&file_name_prefix);
+        let part_2_score = solve_part_2(&input);
         println!("Part 2 - Solution: {score}", score = part_2_score);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_follows_names() {
+        // Sections are out of order, so they can only be chained by name.
+        let almanac =
+            Almanac::parse("seeds: 1 5\n\nb-to-c map:\n10 0 100\n\na-to-b map:\n0 1 1\n").unwrap();
+        let chain = almanac.chain("a", "c").unwrap();
+        assert_eq!(
+            vec!["a", "b"],
+            chain
+                .iter()
+                .map(|map| map.source.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(10, find_location(1, &chain));
+        assert_eq!(15, find_location(5, &chain));
+        assert!(almanac.chain("a", "d").is_err());
+    }
+}
@@ -2,41 +2,55 @@ use std::fs;
 
 #[derive(Debug)]
 enum Mode {
-    TEST,
-    REAL,
+    Test,
+    Real,
 }
 
-fn solve_quadratic(a: f64, b: f64, c: f64) -> Option<(f64, f64)> {
-    let discriminant = b * b - 4.0 * a * c;
-
-    if discriminant < 0.0 {
-        // No real solutions.
-        None
-    } else {
-        let sqrt_discriminant = discriminant.sqrt();
-        let x1 = (-b + sqrt_discriminant) / (2.0 * a);
-        let x2 = (-b - sqrt_discriminant) / (2.0 * a);
-        Some((x1, x2))
+/// Largest integer whose square does not exceed `n`, using Newton's method on integers.
+fn integer_sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Start above the root, so every iteration decreases monotonically towards it.
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
     }
 }
 
-fn is_integer(n: f64) -> bool {
-    n.fract() == 0.0
-}
+/// Number of integers `t` in `0..=time` with `t * (time - t) > distance`, computed exactly.
+fn count_exceeding_products(time: u64, distance: u64) -> u64 {
+    let (time, distance) = (time as u128, distance as u128);
+    let product = |t: u128| t * (time - t);
 
-fn find_race_options(time: &u64, distance: &u64) -> u64 {
-    let (mut x1, mut x2) =
-        solve_quadratic(-1.0, *time as f64, -(*distance as f64)).expect("No solution found.");
-    if is_integer(x1) {
-        x1 += 1.0;
+    // The product peaks in the middle, so without a solution there, there is none at all.
+    let middle = time / 2;
+    if product(middle) <= distance {
+        return 0;
+    }
+
+    // Start from the lower root of t² - time * t + distance and correct the rounding.
+    let root = integer_sqrt(time * time - 4 * distance);
+    let mut low = (time - root) / 2;
+    while product(low) <= distance {
+        low += 1;
     }
-    if is_integer(x2) {
-        x2 -= 1.0;
+    while low > 0 && product(low - 1) > distance {
+        low -= 1;
     }
 
-    let min_time = x1.ceil() as u64;
-    let max_time = x2.floor() as u64;
-    max_time - min_time + 1
+    // Solutions are symmetric around the middle.
+    let high = time - low;
+    (high - low + 1) as u64
+}
+
+fn find_race_options(time: &u64, distance: &u64) -> u64 {
+    // Holding the button for t milliseconds travels t * (time - t) millimeters.
+    count_exceeding_products(*time, *distance)
 }
 
 fn parse_data_part1(input: &str) -> (Vec<u64>, Vec<u64>) {
@@ -86,12 +100,12 @@ fn solve_part_2(input: &str) -> u64 {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let modes = [Mode::TEST, Mode::REAL];
+    let modes = [Mode::Test, Mode::Real];
 
     for mode in modes.iter() {
         let file_name = match mode {
-            Mode::TEST => "test",
-            Mode::REAL => "input",
+            Mode::Test => "test",
+            Mode::Real => "input",
         };
         let file_path = format!("data/{file_name}.txt", file_name = file_name);
         let input = fs::read_to_string(&file_path)?;
@@ -105,3 +119,51 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn brute_force(time: u64, distance: u64) -> u64 {
+        (0..=time).filter(|t| t * (time - t) > distance).count() as u64
+    }
+
+    #[test]
+    fn test_find_race_options() {
+        assert_eq!(4, find_race_options(&7, &9));
+        assert_eq!(8, find_race_options(&15, &40));
+        // Both roots are exact integers, which must not be counted.
+        assert_eq!(9, find_race_options(&30, &200));
+        assert_eq!(71503, find_race_options(&71530, &940200));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force(time in 0u64..2_000, fraction in 0.0f64..1.1) {
+            // Aim for distances around the reachable maximum, where rounding matters most.
+            let distance = (fraction * (time * time / 4) as f64) as u64;
+            prop_assert_eq!(brute_force(time, distance), count_exceeding_products(time, distance));
+        }
+
+        #[test]
+        fn finds_exact_bounds_for_large_races(time in 0u64..u64::MAX, fraction in 0.0f64..1.0) {
+            let distance = (fraction * (time / 2) as f64 * (time - time / 2) as f64) as u64;
+            let count = count_exceeding_products(time, distance) as u128;
+            let (time, distance) = (time as u128, distance as u128);
+            if count == 0 {
+                // Not even the best possible hold time beats the distance.
+                let middle = time / 2;
+                prop_assert!(middle * (time - middle) <= distance);
+            } else {
+                let low = (time + 1 - count) / 2;
+                let high = low + count - 1;
+                // The counted range is exactly the range of solutions.
+                prop_assert!(low * (time - low) > distance);
+                prop_assert!(high * (time - high) > distance);
+                prop_assert!(low == 0 || (low - 1) * (time - low + 1) <= distance);
+                prop_assert!(high == time || (high + 1) * (time - high - 1) <= distance);
+            }
+        }
+    }
+}
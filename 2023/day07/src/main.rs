@@ -1,54 +1,53 @@
-use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fs;
 use std::str::FromStr;
 
 #[derive(Debug)]
 enum Mode {
-    TEST,
-    REAL,
+    Test,
+    Real,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
-enum Card {
-    J,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    T,
-    // J,
-    Q,
-    K,
-    A,
+/// How cards rank against each other and which card, if any, acts as a wildcard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RuleSet {
+    // Weakest card first.
+    order: Vec<char>,
+    wildcard: Option<char>,
 }
 
-impl From<char> for Card {
-    fn from(card: char) -> Self {
-        match card {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::T,
-            'J' => Card::J,
-            'Q' => Card::Q,
-            'K' => Card::K,
-            'A' => Card::A,
-            _ => panic!("No valid card."),
+impl RuleSet {
+    fn standard() -> Self {
+        RuleSet::custom("23456789TJQKA", None).expect("Standard rules are valid.")
+    }
+
+    /// Jokers complete the best possible hand, but are the weakest individual card.
+    fn jokers_wild() -> Self {
+        RuleSet::custom("J23456789TQKA", Some('J')).expect("Joker rules are valid.")
+    }
+
+    fn custom(order: &str, wildcard: Option<char>) -> Result<Self, String> {
+        let order: Vec<char> = order.chars().collect();
+        for (index, card) in order.iter().enumerate() {
+            if order[..index].contains(card) {
+                return Err(format!("Card {card} is ranked twice."));
+            }
+        }
+        if let Some(wildcard) = wildcard.filter(|wildcard| !order.contains(wildcard)) {
+            return Err(format!("Wildcard {wildcard} is not ranked."));
         }
+        Ok(RuleSet { order, wildcard })
+    }
+
+    fn strength(&self, card: char) -> Result<usize, String> {
+        self.order
+            .iter()
+            .position(|&other| other == card)
+            .ok_or_else(|| format!("No valid card: {card}"))
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 enum HandType {
     HighCard,
     OnePair,
@@ -59,127 +58,122 @@ enum HandType {
     FiveOfAKind,
 }
 
-impl FromStr for HandType {
-    type Err = ();
+impl HandType {
+    /// Classify a hand by how often its cards occur, most frequent first.
+    fn from_multiplicities(multiplicities: &[usize]) -> HandType {
+        match multiplicities {
+            [5, ..] => HandType::FiveOfAKind,
+            [4, ..] => HandType::FourOfAKind,
+            [3, 2, ..] => HandType::FullHouse,
+            [3, ..] => HandType::ThreeOfAKind,
+            [2, 2, ..] => HandType::TwoPair,
+            [2, ..] => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
+}
+
+// Hand types are only defined for hands of exactly this many cards.
+const HAND_SIZE: usize = 5;
 
-    fn from_str(hand: &str) -> Result<Self, Self::Err> {
-        let mut counts: HashMap<char, u32> = HashMap::new();
-        hand.chars().for_each(|card| {
-            *counts.entry(card).or_insert(0) += 1;
-        });
-
-        let frequencies = counts
-            .values()
-            .fold([0 as u32; 5], |mut frequencies, &count| {
-                frequencies[(count - 1) as usize] += 1;
-                frequencies
-            });
-
-        Ok(match frequencies {
-            [0, 0, 0, 0, 1] => HandType::FiveOfAKind,
-            [_, 0, 0, 1, 0] => HandType::FourOfAKind,
-            [0, 1, 1, 0, 0] => HandType::FullHouse,
-            [_, 0, 1, 0, 0] => HandType::ThreeOfAKind,
-            [_, 2, 0, 0, 0] => HandType::TwoPair,
-            [_, 1, 0, 0, 0] => HandType::OnePair,
-            [_, 0, 0, 0, 0] => HandType::HighCard,
-            _ => return Err(()),
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Hand {
+    cards: Vec<char>,
+    bid: u32,
+}
+
+impl FromStr for Hand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (cards, bid) = line
+            .split_once(' ')
+            .ok_or_else(|| format!("Either hand or bid not present: {line}"))?;
+        let cards: Vec<char> = cards.chars().collect();
+        if cards.len() != HAND_SIZE {
+            return Err(format!("Hand should have {HAND_SIZE} cards: {line}"));
+        }
+        Ok(Hand {
+            cards,
+            bid: bid
+                .trim()
+                .parse()
+                .map_err(|_| format!("No valid bid: {bid}"))?,
         })
     }
 }
 
-fn compare_hands(a: &(HandType, Vec<Card>, u32), b: &(HandType, Vec<Card>, u32)) -> Ordering {
-    let hand_type_order = a.0.cmp(&b.0);
-    if hand_type_order != Ordering::Equal {
-        return hand_type_order;
-    }
-    a.1.iter().cmp(b.1.iter())
+/// Orders hands by type first and by the strength of each card, in order, second.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct HandKey {
+    hand_type: HandType,
+    strengths: Vec<usize>,
 }
 
-fn compute_score(hands: &Vec<(HandType, Vec<Card>, u32)>) -> u32 {
-    hands
-        .iter()
-        .enumerate()
-        .map(|(index, (_, _, rank))| (index + 1) as u32 * rank)
-        .sum()
+impl Hand {
+    fn key(&self, rules: &RuleSet) -> Result<HandKey, String> {
+        let strengths = self
+            .cards
+            .iter()
+            .map(|&card| rules.strength(card))
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for &card in &self.cards {
+            *counts.entry(card).or_insert(0) += 1;
+        }
+        let wildcards = rules
+            .wildcard
+            .and_then(|wildcard| counts.remove(&wildcard))
+            .unwrap_or(0);
+        let mut multiplicities: Vec<usize> = counts.into_values().collect();
+        multiplicities.sort_unstable_by(|a, b| b.cmp(a));
+        // Wildcards always do best by joining the most frequent card.
+        match multiplicities.first_mut() {
+            Some(most_frequent) => *most_frequent += wildcards,
+            None => multiplicities.push(wildcards),
+        }
+
+        Ok(HandKey {
+            hand_type: HandType::from_multiplicities(&multiplicities),
+            strengths,
+        })
+    }
 }
 
-fn solve_part_1(input: &str) -> u32 {
+fn total_winnings(input: &str, rules: &RuleSet) -> Result<u32, String> {
     let mut hands = input
         .lines()
+        .filter(|line| !line.trim().is_empty())
         .map(|line| {
-            let (hand_string, rank_str) = line
-                .split_once(" ")
-                .expect("Either hand or rank not present.");
-            let hand_type = hand_string.parse::<HandType>().expect("No valid hand.");
-            let cards = hand_string
-                .chars()
-                .map(|card| Card::from(card))
-                .collect::<Vec<Card>>();
-            let rank = rank_str.parse::<u32>().expect("No valid rank.");
-            (hand_type, cards, rank)
+            let hand: Hand = line.parse()?;
+            Ok((hand.key(rules)?, hand.bid))
         })
-        .collect::<Vec<(HandType, Vec<Card>, u32)>>();
+        .collect::<Result<Vec<(HandKey, u32)>, String>>()?;
 
-    hands.sort_by(compare_hands);
-    compute_score(&hands)
+    hands.sort();
+    Ok(hands
+        .iter()
+        .enumerate()
+        .map(|(index, (_, bid))| (index + 1) as u32 * bid)
+        .sum())
 }
 
-fn adjust_hand_type(number_of_jokers: &u32, hand_type: &HandType) -> HandType {
-    match (number_of_jokers, hand_type) {
-        (1, HandType::HighCard) => HandType::OnePair,
-        (1, HandType::OnePair) => HandType::ThreeOfAKind,
-        (1, HandType::TwoPair) => HandType::FullHouse,
-        (1, HandType::ThreeOfAKind) => HandType::FourOfAKind,
-        (1, HandType::FourOfAKind) => HandType::FiveOfAKind,
-        (2, HandType::HighCard) => HandType::ThreeOfAKind,
-        (2, HandType::OnePair) => HandType::FourOfAKind,
-        (2, HandType::TwoPair) => HandType::FourOfAKind,
-        (2, HandType::ThreeOfAKind) => HandType::FiveOfAKind,
-        (3, HandType::HighCard) => HandType::FourOfAKind,
-        (3, HandType::OnePair) => HandType::FiveOfAKind,
-        (4, HandType::HighCard) => HandType::FiveOfAKind,
-        (5, _) => HandType::FiveOfAKind,
-        (_, _) => hand_type.clone(),
-    }
+fn solve_part_1(input: &str) -> u32 {
+    total_winnings(input, &RuleSet::standard()).expect("No valid hands.")
 }
 
 fn solve_part_2(input: &str) -> u32 {
-    let mut hands = input
-        .lines()
-        .map(|line| {
-            let (hand_string, rank_str) = line
-                .split_once(" ")
-                .expect("Either hand or rank not present.");
-            let adjusted_hand_string = hand_string
-                .chars()
-                .filter(|&c| c != 'J')
-                .collect::<String>();
-            let hand_type = adjusted_hand_string
-                .parse::<HandType>()
-                .expect("No valid hand.");
-            let number_of_jokers = hand_string.len() as u32 - adjusted_hand_string.len() as u32;
-            let adjusted_hand_type = adjust_hand_type(&number_of_jokers, &hand_type);
-            let cards: Vec<Card> = hand_string
-                .chars()
-                .map(|card| Card::from(card))
-                .collect::<Vec<Card>>();
-            let rank = rank_str.parse::<u32>().expect("No valid rank.");
-            (adjusted_hand_type, cards, rank)
-        })
-        .collect::<Vec<(HandType, Vec<Card>, u32)>>();
-
-    hands.sort_by(compare_hands);
-    compute_score(&hands)
+    total_winnings(input, &RuleSet::jokers_wild()).expect("No valid hands.")
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let modes = [Mode::TEST, Mode::REAL];
+    let modes = [Mode::Test, Mode::Real];
 
     for mode in modes.iter() {
         let file_name = match mode {
-            Mode::TEST => "test",
-            Mode::REAL => "input",
+            Mode::Test => "test",
+            Mode::Real => "input",
         };
         let file_path = format!("data/{file_name}.txt", file_name = file_name);
         let input = fs::read_to_string(&file_path)?;
@@ -193,3 +187,54 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand_type(cards: &str, rules: &RuleSet) -> HandType {
+        let hand: Hand = format!("{cards} 1").parse().unwrap();
+        hand.key(rules).unwrap().hand_type
+    }
+
+    #[test]
+    fn test_jokers_complete_best_hand() {
+        let rules = RuleSet::jokers_wild();
+        assert_eq!(HandType::FourOfAKind, hand_type("QJJQ2", &rules));
+        assert_eq!(HandType::FullHouse, hand_type("2233J", &rules));
+        assert_eq!(HandType::FiveOfAKind, hand_type("JJJJJ", &rules));
+        assert_eq!(HandType::OnePair, hand_type("2345J", &rules));
+        assert_eq!(HandType::TwoPair, hand_type("2233J", &RuleSet::standard()));
+    }
+
+    #[test]
+    fn test_standard_rules_rank_jack_above_ten() {
+        // Both hands are high card, so the first card decides: J sits between T and Q.
+        let rules = RuleSet::standard();
+        let jack: Hand = "J2345 1".parse().unwrap();
+        let ten: Hand = "T2345 1".parse().unwrap();
+        let queen: Hand = "Q2345 1".parse().unwrap();
+        assert_eq!(HandType::HighCard, jack.key(&rules).unwrap().hand_type);
+        assert!(jack.key(&rules).unwrap() > ten.key(&rules).unwrap());
+        assert!(jack.key(&rules).unwrap() < queen.key(&rules).unwrap());
+    }
+
+    #[test]
+    fn test_hand_size() {
+        assert!("AAAAAA 1".parse::<Hand>().is_err());
+        assert!("AAAA 1".parse::<Hand>().is_err());
+        assert!("AAAAA 1".parse::<Hand>().is_ok());
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        // Twos are strongest and wild, so they beat a natural hand of the same type.
+        let rules = RuleSet::custom("3456789TJQKA2", Some('2')).unwrap();
+        let wild: Hand = "22AAK 1".parse().unwrap();
+        let natural: Hand = "AAAAK 1".parse().unwrap();
+        assert!(wild.key(&rules).unwrap() > natural.key(&rules).unwrap());
+        assert!(RuleSet::custom("22", None).is_err());
+        assert!(RuleSet::custom("23", Some('4')).is_err());
+        assert!(Hand::key(&"2345X 1".parse().unwrap(), &rules).is_err());
+    }
+}
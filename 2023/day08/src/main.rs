@@ -3,8 +3,8 @@ use std::fs;
 
 #[derive(Debug)]
 enum Mode {
-    TEST,
-    REAL,
+    Test,
+    Real,
 }
 
 fn parse_data(input: &str) -> (Vec<char>, HashMap<&str, (&str, &str)>) {
@@ -51,63 +51,234 @@ fn solve_part_1(input: &str) -> u64 {
     steps
 }
 
-fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        a
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Solves the system `t ≡ residue (mod modulus)` for all given pairs, where moduli need not be
+/// coprime. Returns the smallest non-negative solution and the lcm of the moduli, or `None` when
+/// the congruences contradict each other or the lcm does not fit in a `u128`.
+fn chinese_remainder_u128(congruences: &[(u128, u128)]) -> Option<(u128, u128)> {
+    let (mut residue, mut modulus): (u128, u128) = (0, 1);
+    for &(other_residue, other_modulus) in congruences {
+        if other_modulus == 0 {
+            return None;
+        }
+        let other_residue = other_residue % other_modulus;
+        let g = gcd_u128(modulus, other_modulus);
+        // The difference between both residues must be a multiple of the gcd.
+        let (difference, negative) = if other_residue >= residue {
+            (other_residue - residue, false)
+        } else {
+            (residue - other_residue, true)
+        };
+        if difference % g != 0 {
+            return None;
+        }
+
+        // Solve (modulus / g) * steps ≡ difference / g (mod other_modulus / g).
+        let step_modulus = other_modulus / g;
+        let mut target = (difference / g) % step_modulus;
+        if negative {
+            target = (step_modulus - target) % step_modulus;
+        }
+        let inverse = mod_inverse_u128(modulus / g % step_modulus, step_modulus)?;
+        let steps = mul_mod_u128(target, inverse, step_modulus);
+
+        // Step from the current solution in multiples of the current modulus.
+        let new_modulus = modulus.checked_mul(step_modulus)?;
+        residue = (residue + modulus * steps) % new_modulus;
+        modulus = new_modulus;
+    }
+    Some((residue, modulus))
+}
+
+/// Like `chinese_remainder_u128`, but also returns `None` when the lcm does not fit in a `u64`.
+fn chinese_remainder(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let congruences: Vec<(u128, u128)> = congruences
+        .iter()
+        .map(|&(residue, modulus)| (residue as u128, modulus as u128))
+        .collect();
+    let (residue, modulus) = chinese_remainder_u128(&congruences)?;
+    Some((residue as u64, u64::try_from(modulus).ok()?))
+}
+
+/// `a * b mod modulus` without overflowing, by doubling.
+fn mul_mod_u128(mut a: u128, mut b: u128, modulus: u128) -> u128 {
+    let mut result = 0;
+    a %= modulus;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_u128(result, a, modulus);
+        }
+        a = add_mod_u128(a, a, modulus);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_mod_u128(a: u128, b: u128, modulus: u128) -> u128 {
+    // Both operands are below the modulus, so subtracting first cannot underflow.
+    if a >= modulus - b {
+        a - (modulus - b)
     } else {
-        gcd(b, a % b)
+        a + b
+    }
+}
+
+/// Modular inverse on `u128`, using the extended Euclidean algorithm on residues only.
+fn mod_inverse_u128(a: u128, modulus: u128) -> Option<u128> {
+    if modulus == 1 {
+        return Some(0);
+    }
+    // Track the Bézout coefficient of `a` modulo `modulus`, so it never goes negative.
+    let (mut old_r, mut r) = (a % modulus, modulus);
+    let (mut old_x, mut x) = (1u128, 0u128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        let product = mul_mod_u128(quotient, x, modulus);
+        (old_x, x) = (
+            x,
+            add_mod_u128(old_x, (modulus - product) % modulus, modulus),
+        );
+    }
+    (old_r == 1).then_some(old_x)
+}
+
+/// The path of a single ghost, which must eventually repeat a (node, instruction) state.
+#[derive(Debug, PartialEq, Eq)]
+struct Walk {
+    // Steps before the ghost enters its cycle.
+    tail_length: u64,
+    cycle_length: u64,
+    // Steps at which the ghost is on a `Z` node before entering the cycle.
+    tail_hits: Vec<u64>,
+    // Steps at which the ghost is on a `Z` node during the first pass through the cycle.
+    cycle_hits: Vec<u64>,
+}
+
+impl Walk {
+    fn is_on_end_node(&self, step: u64) -> bool {
+        if step < self.tail_length {
+            self.tail_hits.contains(&step)
+        } else {
+            let offset = (step - self.tail_length) % self.cycle_length;
+            self.cycle_hits.contains(&(self.tail_length + offset))
+        }
+    }
+
+    /// The plain LCM shortcut assumes each ghost first reaches `Z` after exactly one cycle
+    /// length, and then again every cycle length.
+    fn allows_lcm_shortcut(&self) -> bool {
+        self.tail_hits.is_empty() && self.cycle_hits == [self.cycle_length]
     }
 }
 
-fn lcm(a: u64, b: u64) -> u64 {
-    a / gcd(a, b) * b
+fn walk(instructions: &[char], network: &HashMap<&str, (&str, &str)>, start: &str) -> Walk {
+    let mut first_visits: HashMap<(&str, usize), u64> = HashMap::new();
+    let mut end_node_steps = Vec::new();
+    let mut current_node = start;
+    let mut step: u64 = 0;
+
+    loop {
+        let instruction_index = step as usize % instructions.len();
+        if let Some(&first_visit) = first_visits.get(&(current_node, instruction_index)) {
+            let (tail_hits, cycle_hits) = end_node_steps
+                .iter()
+                .partition(|&&hit_step| hit_step < first_visit);
+            return Walk {
+                tail_length: first_visit,
+                cycle_length: step - first_visit,
+                tail_hits,
+                cycle_hits,
+            };
+        }
+        first_visits.insert((current_node, instruction_index), step);
+        if current_node.ends_with('Z') {
+            end_node_steps.push(step);
+        }
+
+        let &(element1, element2) = network.get(current_node).expect("Invalid node.");
+        current_node = if instructions[instruction_index] == 'L' {
+            element1
+        } else {
+            element2
+        };
+        step += 1;
+    }
 }
 
-fn lcm_of_vec(numbers: &Vec<u64>) -> u64 {
-    numbers
+/// The first step at which every ghost is on a `Z` node at once.
+fn align_walks(walks: &[Walk]) -> Option<u64> {
+    // Before all ghosts are cycling, try every early hit of any ghost directly.
+    let max_tail_length = walks.iter().map(|walk| walk.tail_length).max()?;
+    let early_hit = walks
+        .iter()
+        .flat_map(|walk| walk.tail_hits.iter().chain(walk.cycle_hits.iter()))
+        .filter(|&&step| step < max_tail_length)
+        .filter(|&&step| walks.iter().all(|walk| walk.is_on_end_node(step)))
+        .min();
+    if early_hit.is_some() {
+        return early_hit.copied();
+    }
+
+    // Afterwards, each ghost is on a `Z` node when the step is congruent to one of its cycle hits.
+    // Try every combination of hits and keep the earliest common step.
+    let mut combinations: Vec<Vec<(u64, u64)>> = vec![Vec::new()];
+    for walk in walks {
+        combinations = combinations
+            .iter()
+            .flat_map(|congruences| {
+                walk.cycle_hits.iter().map(move |&hit| {
+                    let mut congruences = congruences.clone();
+                    congruences.push((hit % walk.cycle_length, walk.cycle_length));
+                    congruences
+                })
+            })
+            .collect();
+    }
+    combinations
         .iter()
-        .copied()
-        .reduce(|a, b| lcm(a, b))
-        .unwrap_or(1)
+        .filter_map(|congruences| chinese_remainder(congruences))
+        .filter_map(|(residue, modulus)| {
+            // The smallest solution from which every ghost is cycling.
+            if residue >= max_tail_length {
+                Some(residue)
+            } else {
+                (max_tail_length - residue)
+                    .div_ceil(modulus)
+                    .checked_mul(modulus)?
+                    .checked_add(residue)
+            }
+        })
+        .min()
 }
 
 fn solve_part_2(input: &str) -> u64 {
     let (instructions, network) = parse_data(input);
-    let starting_nodes = network
+    let walks: Vec<Walk> = network
         .keys()
-        .filter(|&node| node.ends_with("A"))
-        .copied()
-        .collect::<Vec<&str>>();
-
-    let mut steps = Vec::new();
-    starting_nodes.iter().for_each(|&node| {
-        let mut current_node = node;
-        let mut steps_for_node: u64 = 0;
-        for instruction in instructions.iter().cycle() {
-            if current_node.ends_with('Z') {
-                break;
-            }
+        .filter(|&node| node.ends_with('A'))
+        .map(|node| walk(&instructions, &network, node))
+        .collect();
 
-            steps_for_node += 1;
-            let &(element1, element2) = network.get(current_node).expect("Invalid node.");
-            current_node = if *instruction == 'L' {
-                element1
-            } else {
-                element2
-            };
-        }
-        steps.push(steps_for_node);
-    });
-    lcm_of_vec(&steps)
+    if walks.iter().all(Walk::allows_lcm_shortcut) {
+        println!("Note: every ghost cycles cleanly, so the plain LCM of cycle lengths also works.");
+    }
+    align_walks(&walks).expect("Ghosts never align on Z nodes.")
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let modes = [Mode::TEST, Mode::REAL];
+    let modes = [Mode::Test, Mode::Real];
 
     for mode in modes.iter() {
         let file_name = match mode {
-            Mode::TEST => "test",
-            Mode::REAL => "input",
+            Mode::Test => "test",
+            Mode::Real => "input",
         };
         let file_path = format!("data/{file_name}.txt", file_name = file_name);
         let input = fs::read_to_string(&file_path)?;
@@ -121,3 +292,58 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_finds_tail_and_cycle() {
+        let input = "LR\n\n22A = (22B, XXX)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)\nXXX = (XXX, XXX)\n";
+        let (instructions, network) = parse_data(input);
+        let walk = walk(&instructions, &network, "22A");
+        assert_eq!(1, walk.tail_length);
+        assert_eq!(6, walk.cycle_length);
+        assert_eq!(vec![3, 6], walk.cycle_hits);
+        assert!(!walk.allows_lcm_shortcut());
+    }
+
+    #[test]
+    fn test_align_walks_with_offsets() {
+        // Neither ghost starts its cycle at step 0, so the LCM of 4 and 6 (12) is wrong.
+        let walks = [
+            Walk {
+                tail_length: 2,
+                cycle_length: 4,
+                tail_hits: vec![],
+                cycle_hits: vec![3],
+            },
+            Walk {
+                tail_length: 1,
+                cycle_length: 6,
+                tail_hits: vec![],
+                cycle_hits: vec![5],
+            },
+        ];
+        assert_eq!(Some(11), align_walks(&walks));
+        assert_eq!(
+            Some(6),
+            chinese_remainder(&[(2, 4), (0, 6)]).map(|(r, _)| r)
+        );
+        assert_eq!(None, chinese_remainder(&[(1, 4), (0, 6)]));
+    }
+
+    #[test]
+    fn test_chinese_remainder_large_moduli() {
+        // Two primes just below 2^63, whose lcm only fits in a u128.
+        let (p, q) = (9_223_372_036_854_775_783u128, 9_223_372_036_854_775_643u128);
+        let (residue, modulus) = chinese_remainder_u128(&[(1, p), (2, q)]).unwrap();
+        assert_eq!(p * q, modulus);
+        assert_eq!((1, 2), (residue % p, residue % q));
+        assert_eq!(None, chinese_remainder(&[(1, p as u64), (2, q as u64)]));
+        assert_eq!(Some(3), mod_inverse_u128(5, 7));
+        assert_eq!(None, mod_inverse_u128(4, 6));
+        // Coefficients near the modulus must not overflow while they are reduced.
+        assert_eq!(Some(u128::MAX - 1), mod_inverse_u128(u128::MAX - 1, u128::MAX));
+    }
+}
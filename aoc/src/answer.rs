@@ -0,0 +1,107 @@
+use std::fmt;
+
+/// The answer to one part of a puzzle, kept in a canonical form so that answers compare equal
+/// however they were produced: integers as numbers, single lines as trimmed text, and several
+/// lines as a raster without trailing whitespace.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    Raster(Vec<String>),
+}
+
+impl Answer {
+    /// Parses an answer back from its `Display` form, so answers can be serialised as strings.
+    pub fn parse(text: &str) -> Answer {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim_end)
+            .skip_while(|line| line.is_empty())
+            .collect();
+        let end = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |index| index + 1);
+        match &lines[..end] {
+            // Only text that reads back the same is a number, so `007` keeps its zeros.
+            [line] => match line.trim().parse::<i128>() {
+                Ok(number) if number.to_string() == line.trim() => Answer::Number(number),
+                _ => Answer::Text(line.trim().to_string()),
+            },
+            [] => Answer::Text(String::new()),
+            lines => Answer::Raster(lines.iter().map(|line| line.to_string()).collect()),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Raster(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(number: $integer) -> Self {
+                    Answer::Number(number as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::parse(text)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::parse(&text)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(lines: Vec<String>) -> Self {
+        Answer::parse(&lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_forms() {
+        assert_eq!(Answer::from(42u64), Answer::from(" 42\n"));
+        assert_eq!(Answer::from(-7i32), Answer::from("-7".to_string()));
+        assert_eq!(
+            Answer::Text("RLFNRTNFB".to_string()),
+            Answer::from("RLFNRTNFB\n")
+        );
+        assert_ne!(Answer::from(42u64), Answer::from("43"));
+        assert_eq!(Answer::Text("007".to_string()), Answer::from("007"));
+        assert_eq!(Answer::Text("+5".to_string()), Answer::from("+5"));
+        assert_ne!(Answer::from(7), Answer::from("007"));
+    }
+
+    #[test]
+    fn test_raster_round_trip() {
+        let raster = Answer::from(vec!["#..# ".to_string(), ".##.".to_string()]);
+        assert_eq!(
+            Answer::Raster(vec!["#..#".to_string(), ".##.".to_string()]),
+            raster
+        );
+        assert_eq!(raster, Answer::from(format!("\n{raster}\n\n")));
+        assert_eq!("#..#\n.##.", raster.to_string());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::submit::{parse_reply, Reply};
+
+pub const DEFAULT_ENDPOINT: &str = "https://adventofcode.com";
+
+/// Talks to the puzzle site, or anything that answers like it at `endpoint`.
+pub struct Client {
+    endpoint: String,
+    session: Option<String>,
+}
+
+impl Client {
+    pub fn new(endpoint: &str, session: Option<String>) -> Client {
+        Client {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            session,
+        }
+    }
+
+    pub fn submit(&self, day: Day, part: u8, answer: &Answer) -> Result<Reply, String> {
+        let url = format!("{}/{}/day/{}/answer", self.endpoint, day.year, day.day);
+        let mut request = ureq::post(&url);
+        if let Some(session) = &self.session {
+            request = request.set("Cookie", &format!("session={session}"));
+        }
+        let body = request
+            .send_form(&[
+                ("level", &part.to_string()),
+                ("answer", &answer.to_string()),
+            ])
+            .map_err(|error| format!("Failed to submit to {url}: {error}"))?
+            .into_string()
+            .map_err(|error| format!("Failed to read the response from {url}: {error}"))?;
+        Ok(parse_reply(&body))
+    }
+
+    /// The JSON export of a private leaderboard.
+    pub fn leaderboard(&self, year: u16, id: &str) -> Result<String, String> {
+        let url = format!(
+            "{}/{year}/leaderboard/private/view/{id}.json",
+            self.endpoint
+        );
+        let mut request = ureq::get(&url);
+        if let Some(session) = &self.session {
+            request = request.set("Cookie", &format!("session={session}"));
+        }
+        request
+            .call()
+            .map_err(|error| format!("Failed to fetch {url}: {error}"))?
+            .into_string()
+            .map_err(|error| format!("Failed to read the response from {url}: {error}"))
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use crate::answer::Answer;
+
+/// Mode of answers to the real puzzle input, which are the ones submitted.
+pub const REAL_MODE: &str = "Real";
+/// Mode of answers printed before any `Mode:` header, which may be for either input.
+pub const UNKNOWN_MODE: &str = "Unknown";
+
+/// A puzzle day, living in `YEAR/dayDD` as its own crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl Day {
+    pub fn new(year: u16, day: u8) -> Result<Day, String> {
+        if !(1..=25).contains(&day) {
+            return Err(format!("Day should be in 1..=25: {day}"));
+        }
+        Ok(Day { year, day })
+    }
+
+    pub fn name(&self) -> String {
+        format!("day{:02}", self.day)
+    }
+
+    pub fn directory(&self, root: &Path) -> PathBuf {
+        root.join(self.year.to_string()).join(self.name())
+    }
+
+    /// Builds the day in release mode, so repeated runs only recompile what changed.
+    pub fn build(&self, root: &Path) -> Result<(), String> {
+        let directory = self.directory(root);
+        if !directory.join("Cargo.toml").exists() {
+            return Err(format!(
+                "{self} is not a Rust crate: {}",
+                directory.display()
+            ));
+        }
+        let status = Command::new("cargo")
+            .args(["build", "--release", "--quiet"])
+            .current_dir(&directory)
+            .status()
+            .map_err(|error| format!("Failed to start cargo: {error}"))?;
+        if !status.success() {
+            return Err(format!("Failed to build {self}."));
+        }
+        Ok(())
+    }
+
+    /// The built day, to run from its own directory, where it expects its `data/`.
+    fn command(&self, root: &Path, arguments: &[&str]) -> Command {
+        let directory = self.directory(root);
+        let mut command = Command::new(directory.join("target/release").join(self.name()));
+        command.args(arguments).current_dir(&directory);
+        command
+    }
+
+    /// Runs the built day and returns what it printed.
+    pub fn run(&self, root: &Path, arguments: &[&str]) -> Result<String, String> {
+        let output = self
+            .command(root, arguments)
+            .output()
+            .map_err(|error| format!("Failed to run {self}: {error}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{self} failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Starts the built day with its output piped, to read it while the day is still running.
+    pub fn spawn(&self, root: &Path, arguments: &[&str]) -> Result<Child, String> {
+        self.command(root, arguments)
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|error| format!("Failed to run {self}: {error}"))
+    }
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} day {:02}", self.year, self.day)
+    }
+}
+
+/// An answer printed by a day, for the input selected by `mode`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub mode: String,
+    pub part: u8,
+    pub answer: Answer,
+}
+
+/// Parses a `Part N - Label: answer` line into the part and the answer text.
+fn parse_part_line(line: &str) -> Option<(u8, &str)> {
+    let (part, rest) = line.strip_prefix("Part ")?.split_once(" - ")?;
+    let (_, answer) = rest.split_once(':')?;
+    Some((part.trim().parse().ok()?, answer.trim()))
+}
+
+fn is_header(line: &str) -> bool {
+    line.starts_with("Mode: ") || parse_part_line(line).is_some()
+}
+
+/// Collects the answers from the output of a day. Answers follow their `Part N` label on the same
+/// line, or on the next lines up to a blank line for rasters. `Mode:` lines switch between the
+/// test and real input, and answers before the first one have an unknown mode; any other line is
+/// ignored.
+pub fn parse_output(output: &str) -> Vec<Solution> {
+    let mut solutions = Vec::new();
+    let mut mode = UNKNOWN_MODE.to_string();
+    let mut lines = output.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(name) = line.strip_prefix("Mode: ") {
+            mode = name.trim().to_string();
+        } else if let Some((part, answer)) = parse_part_line(line) {
+            let mut text = answer.to_string();
+            if text.is_empty() {
+                while let Some(next) =
+                    lines.next_if(|next| !next.trim().is_empty() && !is_header(next))
+                {
+                    text.push_str(next);
+                    text.push('\n');
+                }
+            }
+            solutions.push(Solution {
+                mode: mode.clone(),
+                part,
+                answer: Answer::from(text),
+            });
+        }
+    }
+    solutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output_with_modes() {
+        let output = "Mode: Test\nPart 1 - Solution: 2\nNote: some remark\nPart 2 - Solution: 6\nMode: Real\nPart 1 - Priorities: 7691\n";
+        let solutions = parse_output(output);
+        assert_eq!(3, solutions.len());
+        assert_eq!(("Test", 2), (solutions[1].mode.as_str(), solutions[1].part));
+        assert_eq!(Answer::from(6), solutions[1].answer);
+        assert_eq!(("Real", 1), (solutions[2].mode.as_str(), solutions[2].part));
+    }
+
+    #[test]
+    fn test_parse_output_with_raster() {
+        let output =
+            "Part 1 - Solution: 11220\nPart 2 - Solution:\n##..\n#..#\nPart 3 - Solution: x\n";
+        let solutions = parse_output(output);
+        assert_eq!(UNKNOWN_MODE, solutions[1].mode);
+        assert_eq!(Answer::from("##..\n#..#"), solutions[1].answer);
+        assert_eq!(Answer::from("x"), solutions[2].answer);
+    }
+
+    /// Renders the `println!` format strings of a day's `main.rs` that print modes or answers, in
+    /// source order, with every placeholder filled in.
+    fn printed_lines(source: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        for line in source.lines() {
+            let Some((_, rest)) = line.split_once("println!(\"") else {
+                continue;
+            };
+            let Some((format, _)) = rest.split_once('"') else {
+                continue;
+            };
+            if !(format.starts_with("Mode: ")
+                || format.starts_with("Part ")
+                || format.contains("Solution"))
+            {
+                continue;
+            }
+            let mut rendered = String::new();
+            let mut rest = format.replace("\\n", "\n");
+            while let Some((before, after)) = rest.split_once('{') {
+                rendered.push_str(before);
+                rendered.push_str(if before.ends_with("Mode: ") {
+                    "Test"
+                } else {
+                    "1"
+                });
+                rest = after
+                    .split_once('}')
+                    .map_or("", |(_, after)| after)
+                    .to_string();
+            }
+            rendered.push_str(&rest);
+            lines.push(rendered);
+        }
+        lines
+    }
+
+    #[test]
+    fn test_parse_output_of_every_day() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut checked = 0;
+        for year in [2022, 2023] {
+            for day in 1..=25 {
+                let day = Day::new(year, day).unwrap();
+                let Ok(source) = std::fs::read_to_string(day.directory(&root).join("src/main.rs"))
+                else {
+                    continue;
+                };
+                let lines = printed_lines(&source);
+                let answers = lines
+                    .iter()
+                    .filter(|line| !line.starts_with("Mode: "))
+                    .count();
+                let solutions = parse_output(&(lines.join("\n") + "\n"));
+                assert!(answers > 0, "{day} prints no answers.");
+                assert_eq!(answers, solutions.len(), "{day} prints unparsable answers.");
+                assert!(
+                    solutions
+                        .iter()
+                        .all(|solution| solution.mode != UNKNOWN_MODE),
+                    "{day} prints answers without a mode."
+                );
+                checked += 1;
+            }
+        }
+        assert!(checked > 20);
+    }
+
+    #[test]
+    fn test_day_location() {
+        let day = Day::new(2023, 8).unwrap();
+        assert_eq!(
+            Path::new("root/2023/day08"),
+            day.directory(Path::new("root"))
+        );
+        assert_eq!("2023 day 08", day.to_string());
+        assert!(Day::new(2023, 26).is_err());
+    }
+}
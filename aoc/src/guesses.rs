@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::answer::Answer;
+use crate::submit::{Reply, Response};
+
+/// What the puzzle site told us about earlier submissions of one part.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartGuesses {
+    pub correct: Option<Answer>,
+    pub wrong: Vec<Answer>,
+    // The largest answer known to be too low and the smallest known to be too high.
+    pub too_low: Option<i128>,
+    pub too_high: Option<i128>,
+}
+
+impl PartGuesses {
+    /// Why `answer` should not be submitted, if it is already known to be wrong.
+    fn rejection(&self, answer: &Answer) -> Option<String> {
+        if let Some(correct) = &self.correct {
+            return Some(if correct == answer {
+                format!("{answer} was already accepted.")
+            } else {
+                format!("The part was already solved with {correct}.")
+            });
+        }
+        if self.wrong.contains(answer) {
+            return Some(format!("{answer} was already rejected."));
+        }
+        if let Answer::Number(number) = answer {
+            if let Some(too_low) = self.too_low.filter(|&too_low| *number <= too_low) {
+                return Some(format!("{number} is at most {too_low}, which is too low."));
+            }
+            if let Some(too_high) = self.too_high.filter(|&too_high| *number >= too_high) {
+                return Some(format!(
+                    "{number} is at least {too_high}, which is too high."
+                ));
+            }
+        }
+        None
+    }
+
+    fn record(&mut self, answer: &Answer, response: &Response) {
+        match response {
+            Response::Correct => self.correct = Some(answer.clone()),
+            Response::TooLow | Response::TooHigh | Response::Wrong => {
+                if !self.wrong.contains(answer) {
+                    self.wrong.push(answer.clone());
+                }
+                if let Answer::Number(number) = answer {
+                    if *response == Response::TooLow {
+                        self.too_low = self.too_low.max(Some(*number));
+                    } else if *response == Response::TooHigh {
+                        self.too_high =
+                            Some(self.too_high.map_or(*number, |high| high.min(*number)));
+                    }
+                }
+            }
+            Response::Wait(_) | Response::WrongLevel | Response::Unknown(_) => {}
+        }
+    }
+}
+
+/// Submissions of a day, stored next to it in `guesses.json`, so that no known-wrong answer is
+/// submitted twice and no answer is submitted before the site allows it.
+pub struct Guesses {
+    path: PathBuf,
+    parts: BTreeMap<u8, PartGuesses>,
+    // Seconds since the Unix epoch before which the site refuses answers.
+    wait_until: Option<u64>,
+}
+
+fn parse_number(value: &Value) -> Option<i128> {
+    value.as_str()?.parse().ok()
+}
+
+impl Guesses {
+    pub const FILE_NAME: &'static str = "guesses.json";
+
+    pub fn load(directory: &Path) -> Result<Guesses, String> {
+        let path = directory.join(Guesses::FILE_NAME);
+        let mut guesses = Guesses {
+            path,
+            parts: BTreeMap::new(),
+            wait_until: None,
+        };
+        if !guesses.path.exists() {
+            return Ok(guesses);
+        }
+        let contents = fs::read_to_string(&guesses.path)
+            .map_err(|error| format!("Failed to read {}: {error}", guesses.path.display()))?;
+        let json: Value = serde_json::from_str(&contents)
+            .map_err(|error| format!("Invalid JSON in {}: {error}", guesses.path.display()))?;
+        guesses.wait_until = json["wait_until"].as_u64();
+        if let Some(parts) = json["parts"].as_object() {
+            for (part, entry) in parts {
+                let part: u8 = part
+                    .parse()
+                    .map_err(|_| format!("Invalid part in guesses: {part}"))?;
+                let answers = |key: &str| -> Vec<Answer> {
+                    entry[key]
+                        .as_array()
+                        .map(|answers| {
+                            answers
+                                .iter()
+                                .filter_map(Value::as_str)
+                                .map(Answer::from)
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                };
+                let part_guesses = PartGuesses {
+                    correct: entry["correct"].as_str().map(Answer::from),
+                    wrong: answers("wrong"),
+                    too_low: parse_number(&entry["too_low"]),
+                    too_high: parse_number(&entry["too_high"]),
+                };
+                guesses.parts.insert(part, part_guesses);
+            }
+        }
+        Ok(guesses)
+    }
+
+    pub fn part(&self, part: u8) -> PartGuesses {
+        self.parts.get(&part).cloned().unwrap_or_default()
+    }
+
+    /// Fails with the reason when `answer` should not be submitted at time `now`.
+    pub fn check(&self, part: u8, answer: &Answer, now: u64) -> Result<(), String> {
+        if let Some(wait_until) = self.wait_until.filter(|&wait_until| now < wait_until) {
+            return Err(format!(
+                "Wait {}s before submitting again.",
+                wait_until - now
+            ));
+        }
+        match self.part(part).rejection(answer) {
+            Some(reason) => Err(reason),
+            None => Ok(()),
+        }
+    }
+
+    /// Remembers what the site said about `answer`, and until when it refuses further answers.
+    pub fn record(&mut self, part: u8, answer: &Answer, reply: &Reply, now: u64) {
+        if let Some(cooldown) = reply.cooldown {
+            self.wait_until = Some(now + cooldown.as_secs());
+        }
+        self.parts
+            .entry(part)
+            .or_default()
+            .record(answer, &reply.response);
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let parts: Map<String, Value> = self
+            .parts
+            .iter()
+            .map(|(part, guesses)| {
+                let entry = json!({
+                    "correct": guesses.correct.as_ref().map(Answer::to_string),
+                    "wrong": guesses.wrong.iter().map(Answer::to_string).collect::<Vec<String>>(),
+                    "too_low": guesses.too_low.map(|number| number.to_string()),
+                    "too_high": guesses.too_high.map(|number| number.to_string()),
+                });
+                (part.to_string(), entry)
+            })
+            .collect();
+        let json = json!({ "wait_until": self.wait_until, "parts": parts });
+        let contents = serde_json::to_string_pretty(&json)
+            .map_err(|error| format!("Failed to serialise guesses: {error}"))?;
+        fs::write(&self.path, contents + "\n")
+            .map_err(|error| format!("Failed to write {}: {error}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bounds_and_bad_guesses() {
+        let directory = std::env::temp_dir().join(format!("aoc-guesses-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let mut guesses = Guesses::load(&directory).unwrap();
+        let reply = |response: Response| Reply {
+            cooldown: None,
+            response,
+        };
+        guesses.record(1, &Answer::from(100), &reply(Response::TooLow), 0);
+        guesses.record(1, &Answer::from(500), &reply(Response::TooHigh), 0);
+        guesses.record(1, &Answer::from(300), &reply(Response::Wrong), 0);
+        let wait = Duration::from_secs(60);
+        let refused = Reply {
+            response: Response::Wait(wait),
+            cooldown: Some(wait),
+        };
+        guesses.record(1, &Answer::from(200), &refused, 10);
+        guesses.save().unwrap();
+
+        let guesses = Guesses::load(&directory).unwrap();
+        assert!(guesses.check(1, &Answer::from(200), 30).is_err());
+        assert!(guesses.check(1, &Answer::from(200), 70).is_ok());
+        assert!(guesses.check(1, &Answer::from(100), 70).is_err());
+        assert!(guesses.check(1, &Answer::from(50), 70).is_err());
+        assert!(guesses.check(1, &Answer::from(600), 70).is_err());
+        assert!(guesses.check(1, &Answer::from(300), 70).is_err());
+        assert!(guesses.check(2, &Answer::from(300), 70).is_ok());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
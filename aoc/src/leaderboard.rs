@@ -0,0 +1,309 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::day::{Day, REAL_MODE};
+use crate::store::AnswerStore;
+
+/// A member of a private leaderboard and the times at which they earned their stars.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Member {
+    pub id: String,
+    pub name: String,
+    pub local_score: u64,
+    // Seconds since the Unix epoch, by day and part.
+    pub stars: BTreeMap<(u8, u8), u64>,
+}
+
+impl Member {
+    /// Anonymous members have no name in the export.
+    fn display_name(&self) -> String {
+        if self.name.is_empty() {
+            format!("(anonymous user #{})", self.id)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+pub struct Leaderboard {
+    pub year: u16,
+    pub members: Vec<Member>,
+}
+
+/// Reads an integer that the export stores either as a number or as a string.
+fn as_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+}
+
+impl Leaderboard {
+    /// Parses the JSON export of a private leaderboard.
+    pub fn parse(json: &str) -> Result<Leaderboard, String> {
+        let json: Value =
+            serde_json::from_str(json).map_err(|error| format!("Invalid leaderboard: {error}"))?;
+        let year = as_u64(&json["event"])
+            .and_then(|year| u16::try_from(year).ok())
+            .ok_or("Leaderboard should name its event.")?;
+        let members = json["members"]
+            .as_object()
+            .ok_or("Leaderboard should list its members.")?;
+
+        let mut parsed = Vec::new();
+        for (id, member) in members {
+            let mut stars = BTreeMap::new();
+            if let Some(days) = member["completion_day_level"].as_object() {
+                for (day, parts) in days {
+                    let day: u8 = day.parse().map_err(|_| format!("Invalid day: {day}"))?;
+                    for (part, star) in parts.as_object().into_iter().flatten() {
+                        let part: u8 = part.parse().map_err(|_| format!("Invalid part: {part}"))?;
+                        let time = as_u64(&star["get_star_ts"])
+                            .ok_or_else(|| format!("Star of {id} has no time."))?;
+                        stars.insert((day, part), time);
+                    }
+                }
+            }
+            parsed.push(Member {
+                id: id.clone(),
+                name: member["name"].as_str().unwrap_or_default().to_string(),
+                local_score: as_u64(&member["local_score"]).unwrap_or(0),
+                stars,
+            });
+        }
+        // Strongest members first, as on the site.
+        parsed.sort_by(|a, b| {
+            b.local_score
+                .cmp(&a.local_score)
+                .then(b.stars.len().cmp(&a.stars.len()))
+                .then(a.id.cmp(&b.id))
+        });
+        Ok(Leaderboard {
+            year,
+            members: parsed,
+        })
+    }
+
+    /// Local scores from the star times: for every star, the first of `n` members to earn it gets
+    /// `n` points, the next `n - 1`, and so on.
+    pub fn local_scores(&self) -> HashMap<&str, u64> {
+        let mut scores: HashMap<&str, u64> = self
+            .members
+            .iter()
+            .map(|member| (member.id.as_str(), 0))
+            .collect();
+        let stars: BTreeSet<(u8, u8)> = self
+            .members
+            .iter()
+            .flat_map(|member| member.stars.keys().copied())
+            .collect();
+        for star in stars {
+            let mut earned: Vec<(u64, &str)> = self
+                .members
+                .iter()
+                .filter_map(|member| Some((*member.stars.get(&star)?, member.id.as_str())))
+                .collect();
+            earned.sort();
+            for (rank, (_, id)) in earned.into_iter().enumerate() {
+                *scores.entry(id).or_insert(0) += (self.members.len() - rank) as u64;
+            }
+        }
+        scores
+    }
+
+    /// The parts of `day` that any member has a star for.
+    pub fn starred_parts(&self, day: u8) -> BTreeSet<u8> {
+        self.members
+            .iter()
+            .flat_map(|member| member.stars.keys())
+            .filter(|&&(star_day, _)| star_day == day)
+            .map(|&(_, part)| part)
+            .collect()
+    }
+
+    pub fn days(&self) -> BTreeSet<u8> {
+        self.members
+            .iter()
+            .flat_map(|member| member.stars.keys().map(|&(day, _)| day))
+            .collect()
+    }
+}
+
+/// Days since the Unix epoch of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Puzzles unlock at midnight in UTC-5, which is 05:00 UTC.
+pub fn unlock_time(year: u16, day: u8) -> u64 {
+    (days_from_civil(year as i64, 12, day as i64) * 86400 + 5 * 3600) as u64
+}
+
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    format!("{hours}:{minutes:02}:{seconds:02}")
+}
+
+/// Whether every part of a day that the leaderboard counts has a stored answer to the real input,
+/// as a day directory alone may just be scaffolding.
+fn solved_here(root: &Path, year: u16, day: u8, parts: &BTreeSet<u8>) -> bool {
+    let Ok(day) = Day::new(year, day) else {
+        return false;
+    };
+    AnswerStore::load(&day.directory(root)).is_ok_and(|store| {
+        parts
+            .iter()
+            // The second star of day 25 comes free with all others and has no answer.
+            .filter(|&&part| !(day.day == 25 && part == 2))
+            .all(|&part| store.get(REAL_MODE, part).is_some())
+    })
+}
+
+/// Star times of every member per day, with the time between both parts, and which days are
+/// solved in this repository.
+pub fn report(leaderboard: &Leaderboard, root: &Path) -> String {
+    let year = leaderboard.year;
+    let solved: BTreeSet<u8> = leaderboard
+        .days()
+        .into_iter()
+        .filter(|&day| solved_here(root, year, day, &leaderboard.starred_parts(day)))
+        .collect();
+    let scores = leaderboard.local_scores();
+    let mut report = String::new();
+
+    let _ = writeln!(
+        report,
+        "Private leaderboard {year}, {} members",
+        leaderboard.members.len()
+    );
+    for member in &leaderboard.members {
+        let _ = writeln!(
+            report,
+            "\n{}: {} stars, local score {} (recomputed {})",
+            member.display_name(),
+            member.stars.len(),
+            member.local_score,
+            scores.get(member.id.as_str()).copied().unwrap_or(0)
+        );
+        for day in 1..=25 {
+            let Some(&first) = member.stars.get(&(day, 1)) else {
+                continue;
+            };
+            let mut line = format!(
+                "  Day {day:02}: part 1 after {}",
+                format_duration(first.saturating_sub(unlock_time(year, day)))
+            );
+            if let Some(&second) = member.stars.get(&(day, 2)) {
+                let _ = write!(
+                    line,
+                    ", part 2 {} later",
+                    format_duration(second.saturating_sub(first))
+                );
+            }
+            if !solved.contains(&day) {
+                line.push_str(" (not solved in this repository)");
+            }
+            let _ = writeln!(report, "{line}");
+        }
+    }
+
+    let (local, missing): (Vec<u8>, Vec<u8>) = leaderboard
+        .days()
+        .into_iter()
+        .partition(|day| solved.contains(day));
+    let list = |days: &[u8]| {
+        days.iter()
+            .map(|day| format!("{day:02}"))
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    let _ = writeln!(report, "\nSolved in this repository: {}", list(&local));
+    if !missing.is_empty() {
+        let _ = writeln!(
+            report,
+            "Starred by the team, but not solved in this repository: {}",
+            list(&missing)
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "event": "2023",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "Ada", "local_score": 5, "stars": 2,
+                  "completion_day_level": {"1": {"1": {"get_star_ts": 1701407000, "star_index": 1},
+                                                 "2": {"get_star_ts": 1701407600, "star_index": 3}}}},
+            "2": {"id": 2, "name": null, "local_score": 3, "stars": 2,
+                  "completion_day_level": {"1": {"1": {"get_star_ts": 1701406900, "star_index": 0},
+                                                 "2": {"get_star_ts": 1701408000, "star_index": 4}}}},
+            "3": {"id": 3, "name": "Grace", "local_score": 0, "stars": 0, "completion_day_level": {}}
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_and_recompute_scores() {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        assert_eq!(2023, leaderboard.year);
+        assert_eq!(
+            vec!["1", "2", "3"],
+            leaderboard
+                .members
+                .iter()
+                .map(|m| m.id.as_str())
+                .collect::<Vec<_>>()
+        );
+        let scores = leaderboard.local_scores();
+        // Member 2 is first on part 1 and member 1 on part 2, each worth 3 points.
+        assert_eq!(Some(&5), scores.get("1"));
+        assert_eq!(Some(&5), scores.get("2"));
+        assert_eq!(Some(&0), scores.get("3"));
+        assert_eq!("(anonymous user #2)", leaderboard.members[1].display_name());
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        // 2023-12-01T05:00:00Z.
+        assert_eq!(1_701_406_800, unlock_time(2023, 1));
+        assert_eq!(1_701_406_800 + 7 * 86400, unlock_time(2023, 8));
+        assert_eq!("1:02:03", format_duration(3723));
+    }
+
+    #[test]
+    fn test_report_cross_references_repository() {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        let root = std::env::temp_dir().join(format!("aoc-leaderboard-{}", std::process::id()));
+        let directory = Day::new(2023, 1).unwrap().directory(&root);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        // A scaffolded day, or one with only part of its answers, is not solved yet.
+        let report_now = || report(&leaderboard, &root);
+        assert!(report_now().contains("Starred by the team, but not solved in this repository: 01"));
+        let mut store = AnswerStore::load(&directory).unwrap();
+        store.insert(REAL_MODE, 1, 142.into());
+        store.save().unwrap();
+        assert!(report_now().contains(
+            "Day 01: part 1 after 0:03:20, part 2 0:10:00 later (not solved in this repository)"
+        ));
+
+        store.insert(REAL_MODE, 2, 281.into());
+        store.save().unwrap();
+        let report = report_now();
+        assert!(report.contains("Ada: 2 stars, local score 5 (recomputed 5)"));
+        assert!(report.contains("Day 01: part 1 after 0:03:20, part 2 0:10:00 later\n"));
+        assert!(report.contains("Solved in this repository: 01"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
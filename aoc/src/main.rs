@@ -0,0 +1,251 @@
+mod answer;
+mod client;
+mod day;
+mod guesses;
+mod leaderboard;
+mod store;
+mod submit;
+mod watch;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::{Parser, Subcommand};
+
+use answer::Answer;
+use client::{Client, DEFAULT_ENDPOINT};
+use day::{parse_output, Day, Solution, REAL_MODE, UNKNOWN_MODE};
+use guesses::Guesses;
+use leaderboard::Leaderboard;
+use store::{AnswerStore, Verdict};
+use submit::Response;
+
+/// Runs and checks the puzzle days in this repository.
+#[derive(Parser)]
+#[command(name = "aoc")]
+struct Cli {
+    /// Base URL of the puzzle site.
+    #[arg(long, global = true, env = "AOC_ENDPOINT", default_value = DEFAULT_ENDPOINT)]
+    endpoint: String,
+    /// Session cookie used to authenticate with the puzzle site.
+    #[arg(long, global = true, env = "AOC_SESSION", hide_env_values = true)]
+    session: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build and run a day, checking its answers against the ones stored next to it.
+    Run {
+        year: u16,
+        day: u8,
+        /// Store the computed answers, replacing any stored ones.
+        #[arg(long)]
+        save: bool,
+        /// Let the day render its visualizations, and show everything it prints.
+        #[arg(long)]
+        visualize: bool,
+    },
+    /// Solve a part of a day and submit its answer, unless it is known to be wrong.
+    Submit { year: u16, day: u8, part: u8 },
+    /// Rerun a day whenever its sources or data change, checking the examples first.
+    ///
+    /// The real input only runs once no example gives a wrong answer. Days that choose their input
+    /// at compile time, as the 2022 days do with `CHOSEN_MODE`, only run that input, so their
+    /// examples are not checked.
+    Watch { year: u16, day: u8 },
+    /// Report star times of a private leaderboard and which of its days are solved here.
+    Leaderboard {
+        year: u16,
+        /// Read the leaderboard from a JSON export instead of fetching it.
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Id of the private leaderboard to fetch.
+        #[arg(long, env = "AOC_LEADERBOARD")]
+        id: Option<String>,
+    },
+}
+
+/// The repository holding the `YEAR/dayDD` crates: `AOC_ROOT` if set, else the parent of this
+/// crate.
+fn repository_root() -> PathBuf {
+    env::var_os("AOC_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(".."))
+}
+
+fn format_answer(answer: &Answer) -> String {
+    match answer {
+        // Start rasters on their own line, so their rows line up.
+        Answer::Raster(_) => format!("\n{answer}"),
+        _ => answer.to_string(),
+    }
+}
+
+/// Builds and runs a day, and returns its answers.
+fn solve(day: Day, root: &Path, visualize: bool) -> Result<Vec<Solution>, String> {
+    day.build(root)?;
+    let output = if visualize {
+        let output = day.run(root, &["--visualize"])?;
+        print!("{output}");
+        output
+    } else {
+        day.run(root, &[])?
+    };
+    let solutions = parse_output(&output);
+    if solutions.is_empty() {
+        return Err(format!("{day} printed no answers."));
+    }
+    Ok(solutions)
+}
+
+fn run(day: Day, save: bool, visualize: bool, root: &Path) -> Result<(), String> {
+    let solutions = solve(day, root, visualize)?;
+    let mut store = AnswerStore::load(&day.directory(root))?;
+
+    println!("{day}");
+    let mut wrong = 0;
+    for solution in &solutions {
+        let (verdict, expected) = match store.check(solution) {
+            Verdict::Correct => ("correct", None),
+            Verdict::Wrong { expected } => ("wrong", Some(expected)),
+            Verdict::Unknown => ("new", None),
+        };
+        println!(
+            "  {} part {} ({verdict}): {}",
+            solution.mode,
+            solution.part,
+            format_answer(&solution.answer)
+        );
+        if let Some(expected) = expected {
+            wrong += 1;
+            println!("    expected: {}", format_answer(&expected));
+        }
+    }
+
+    if save {
+        for solution in solutions {
+            if solution.mode == UNKNOWN_MODE {
+                println!(
+                    "WARNING: Not saving part {}, as {day} does not print its mode.",
+                    solution.part
+                );
+                continue;
+            }
+            store.insert(&solution.mode, solution.part, solution.answer);
+        }
+        store.save()?;
+    } else if wrong > 0 {
+        return Err(format!("{wrong} answer(s) of {day} changed."));
+    }
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn submit(day: Day, part: u8, client: &Client, root: &Path) -> Result<(), String> {
+    let solutions = solve(day, root, false)?;
+    let directory = day.directory(root);
+    let mut store = AnswerStore::load(&directory)?;
+
+    // Only submit answers of a day that still solves its examples.
+    for solution in solutions
+        .iter()
+        .filter(|solution| solution.mode != REAL_MODE)
+    {
+        if let Verdict::Wrong { expected } = store.check(solution) {
+            return Err(format!(
+                "{day} gives {} for {} part {}, expected {expected}.",
+                solution.answer, solution.mode, solution.part
+            ));
+        }
+    }
+    let answer = solutions
+        .into_iter()
+        .find(|solution| solution.mode == REAL_MODE && solution.part == part)
+        .ok_or_else(|| format!("{day} printed no answer to part {part} for the real input."))?
+        .answer;
+    if let Answer::Raster(_) = answer {
+        return Err(format!(
+            "Read the letters off the image and submit them by hand:\n{answer}"
+        ));
+    }
+
+    let mut guesses = Guesses::load(&directory)?;
+    guesses.check(part, &answer, now())?;
+    let reply = client.submit(day, part, &answer)?;
+    guesses.record(part, &answer, &reply, now());
+    guesses.save()?;
+
+    let response = reply.response;
+    let message = match &response {
+        Response::Correct => "correct".to_string(),
+        Response::TooHigh => "too high".to_string(),
+        Response::TooLow => "too low".to_string(),
+        Response::Wrong => "wrong".to_string(),
+        Response::Wait(wait) => format!("not checked, wait {}s", wait.as_secs()),
+        Response::WrongLevel => "not checked, the part is solved or locked".to_string(),
+        Response::Unknown(text) => format!("unknown response: {text}"),
+    };
+    println!("{day} part {part}: {answer} ({message})");
+    // Refusals already state their wait above.
+    if let Some(cooldown) = reply
+        .cooldown
+        .filter(|_| !matches!(response, Response::Wait(_)))
+    {
+        println!("Wait {}s before submitting again.", cooldown.as_secs());
+    }
+    if response == Response::Correct {
+        store.insert(REAL_MODE, part, answer);
+        store.save()?;
+    }
+    Ok(())
+}
+
+fn leaderboard(
+    year: u16,
+    file: Option<PathBuf>,
+    id: Option<String>,
+    client: &Client,
+    root: &Path,
+) -> Result<(), String> {
+    let json = match (file, id) {
+        (Some(file), _) => fs::read_to_string(&file)
+            .map_err(|error| format!("Failed to read {}: {error}", file.display()))?,
+        (None, Some(id)) => client.leaderboard(year, &id)?,
+        (None, None) => return Err("Pass a leaderboard file or id.".to_string()),
+    };
+    let leaderboard = Leaderboard::parse(&json)?;
+    print!("{}", leaderboard::report(&leaderboard, root));
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let root = repository_root();
+    match cli.command {
+        Command::Run {
+            year,
+            day,
+            save,
+            visualize,
+        } => run(Day::new(year, day)?, save, visualize, &root)?,
+        Command::Submit { year, day, part } => {
+            let client = Client::new(&cli.endpoint, cli.session);
+            submit(Day::new(year, day)?, part, &client, &root)?
+        }
+        Command::Watch { year, day } => watch::watch(Day::new(year, day)?, &root)?,
+        Command::Leaderboard { year, file, id } => {
+            let client = Client::new(&cli.endpoint, cli.session);
+            leaderboard(year, file, id, &client, &root)?
+        }
+    }
+    Ok(())
+}
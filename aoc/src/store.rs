@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::answer::Answer;
+use crate::day::Solution;
+
+/// How a computed answer compares to the stored one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: Answer },
+    Unknown,
+}
+
+/// The known answers of a day by mode and part, stored as JSON strings in the `Display` form of
+/// each answer: `{"Real": {"1": "17873"}}`.
+pub struct AnswerStore {
+    path: PathBuf,
+    answers: BTreeMap<String, BTreeMap<u8, Answer>>,
+}
+
+impl AnswerStore {
+    pub const FILE_NAME: &'static str = "answers.json";
+
+    /// Loads the store in `directory`, which is empty if the file does not exist yet.
+    pub fn load(directory: &Path) -> Result<AnswerStore, String> {
+        let path = directory.join(AnswerStore::FILE_NAME);
+        let mut answers = BTreeMap::new();
+        if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+            let json: Value = serde_json::from_str(&contents)
+                .map_err(|error| format!("Invalid JSON in {}: {error}", path.display()))?;
+            let modes = json
+                .as_object()
+                .ok_or_else(|| format!("Answers should be an object: {}", path.display()))?;
+            for (mode, parts) in modes {
+                let parts = parts
+                    .as_object()
+                    .ok_or_else(|| format!("Answers of mode {mode} should be an object."))?;
+                let mut mode_answers = BTreeMap::new();
+                for (part, answer) in parts {
+                    let part: u8 = part
+                        .parse()
+                        .map_err(|_| format!("Invalid part in {mode}: {part}"))?;
+                    let answer = answer.as_str().ok_or_else(|| {
+                        format!("Answer to {mode} part {part} should be a string.")
+                    })?;
+                    mode_answers.insert(part, Answer::from(answer));
+                }
+                answers.insert(mode.clone(), mode_answers);
+            }
+        }
+        Ok(AnswerStore { path, answers })
+    }
+
+    pub fn get(&self, mode: &str, part: u8) -> Option<&Answer> {
+        self.answers.get(mode)?.get(&part)
+    }
+
+    pub fn insert(&mut self, mode: &str, part: u8, answer: Answer) {
+        self.answers
+            .entry(mode.to_string())
+            .or_default()
+            .insert(part, answer);
+    }
+
+    pub fn check(&self, solution: &Solution) -> Verdict {
+        match self.get(&solution.mode, solution.part) {
+            Some(expected) if *expected == solution.answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json: Map<String, Value> = self
+            .answers
+            .iter()
+            .map(|(mode, parts)| {
+                let parts: Map<String, Value> = parts
+                    .iter()
+                    .map(|(part, answer)| (part.to_string(), Value::String(answer.to_string())))
+                    .collect();
+                (mode.clone(), Value::Object(parts))
+            })
+            .collect();
+        let contents = serde_json::to_string_pretty(&Value::Object(json))
+            .map_err(|error| format!("Failed to serialise answers: {error}"))?;
+        fs::write(&self.path, contents + "\n")
+            .map_err(|error| format!("Failed to write {}: {error}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_round_trip() {
+        let directory = std::env::temp_dir().join(format!("aoc-store-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let mut store = AnswerStore::load(&directory).unwrap();
+        store.insert("Real", 1, Answer::from(17873));
+        store.insert("Real", 2, Answer::from("#..#\n.##."));
+        store.save().unwrap();
+
+        let store = AnswerStore::load(&directory).unwrap();
+        let solution = |part, answer| Solution {
+            mode: "Real".to_string(),
+            part,
+            answer,
+        };
+        assert_eq!(
+            Verdict::Correct,
+            store.check(&solution(1, Answer::from(17873)))
+        );
+        assert_eq!(
+            Verdict::Correct,
+            store.check(&solution(2, Answer::from("#..#\n.##.\n")))
+        );
+        assert_eq!(
+            Verdict::Wrong {
+                expected: Answer::from(17873)
+            },
+            store.check(&solution(1, Answer::from(2)))
+        );
+        assert_eq!(Verdict::Unknown, store.check(&solution(3, Answer::from(2))));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,260 @@
+use std::time::Duration;
+
+/// What the puzzle site said about a submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Response {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Answers were submitted too recently; try again after the given time.
+    Wait(Duration),
+    /// The part is already solved, or not unlocked yet.
+    WrongLevel,
+    Unknown(String),
+}
+
+/// Parses a wait such as `1h 4m 30s`, ignoring any other words.
+fn parse_wait(text: &str) -> Option<Duration> {
+    let mut seconds = 0;
+    let mut found = false;
+    for word in text.split_whitespace() {
+        let Some(unit_start) = word.find(|c: char| !c.is_ascii_digit()) else {
+            continue;
+        };
+        let (number, unit) = word.split_at(unit_start);
+        let Ok(number) = number.parse::<u64>() else {
+            continue;
+        };
+        seconds += number
+            * match unit {
+                "h" => 3600,
+                "m" => 60,
+                "s" => 1,
+                _ => continue,
+            };
+        found = true;
+    }
+    found.then(|| Duration::from_secs(seconds))
+}
+
+/// A response together with how long the site asks to wait before the next answer, which it
+/// states after wrong answers as well as when refusing one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reply {
+    pub response: Response,
+    pub cooldown: Option<Duration>,
+}
+
+/// Parses a spelled-out wait such as `one minute` or `5 minutes`.
+fn parse_spelled_wait(text: &str) -> Option<Duration> {
+    const NUMBERS: [&str; 10] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut seconds = 0;
+    let mut found = false;
+    for pair in words.windows(2) {
+        let number = match pair[0].parse::<u64>() {
+            Ok(number) => number,
+            Err(_) => match NUMBERS
+                .iter()
+                .position(|&name| name == pair[0].to_lowercase())
+            {
+                Some(index) => index as u64 + 1,
+                None => continue,
+            },
+        };
+        let unit = pair[1].trim_end_matches(['.', ',', ';']);
+        seconds += number
+            * match unit.trim_end_matches('s') {
+                "hour" => 3600,
+                "minute" => 60,
+                "second" => 1,
+                _ => continue,
+            };
+        found = true;
+    }
+    found.then(|| Duration::from_secs(seconds))
+}
+
+/// The text of the `<article>` in a response page, without tags.
+fn article_text(html: &str) -> String {
+    let article = html
+        .split_once("<article>")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for character in article.chars() {
+        match character {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(character),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn parse_response(html: &str) -> Response {
+    let text = article_text(html);
+    if text.contains("That's the right answer") {
+        Response::Correct
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Response::TooHigh
+        } else if text.contains("your answer is too low") {
+            Response::TooLow
+        } else {
+            Response::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        let wait = text
+            .split_once("You have ")
+            .and_then(|(_, rest)| rest.split_once(" left to wait"))
+            .and_then(|(wait, _)| parse_wait(wait))
+            .unwrap_or(Duration::from_secs(60));
+        Response::Wait(wait)
+    } else if text.contains("You don't seem to be solving the right level") {
+        Response::WrongLevel
+    } else {
+        Response::Unknown(text)
+    }
+}
+
+pub fn parse_reply(html: &str) -> Reply {
+    let response = parse_response(html);
+    let cooldown = match &response {
+        Response::Wait(wait) => Some(*wait),
+        _ => {
+            let text = article_text(html);
+            text.to_lowercase()
+                .split_once("please wait ")
+                .and_then(|(_, rest)| rest.split_once(" before trying again"))
+                .and_then(|(wait, _)| parse_spelled_wait(wait))
+        }
+    };
+    Reply { response, cooldown }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Answer;
+    use crate::client::Client;
+    use crate::day::Day;
+    use crate::guesses::Guesses;
+    use std::thread;
+
+    fn page(message: &str) -> String {
+        format!("<html><main><article><p>{message}</p></article></main></html>")
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let too_low = "That's not the right answer; your answer is too low. <a href=\"/2023/day/8\">[Return to Day 8]</a>";
+        assert_eq!(Response::TooLow, parse_response(&page(too_low)));
+        assert_eq!(
+            Response::Correct,
+            parse_response(&page(
+                "That's the right answer! You are <em>one gold star</em> closer."
+            ))
+        );
+        assert_eq!(
+            Response::Wait(Duration::from_secs(4 * 60 + 30)),
+            parse_response(&page(
+                "You gave an answer too recently. You have 4m 30s left to wait."
+            ))
+        );
+        assert_eq!(
+            Response::WrongLevel,
+            parse_response(&page(
+                "You don't seem to be solving the right level. Did you already complete it?"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_cooldown() {
+        let reply = parse_reply(&page(
+            "That's not the right answer. Please wait one minute before trying again.",
+        ));
+        assert_eq!(Response::Wrong, reply.response);
+        assert_eq!(Some(Duration::from_secs(60)), reply.cooldown);
+        let reply = parse_reply(&page(
+            "That's not the right answer; your answer is too low. Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again.",
+        ));
+        assert_eq!(Some(Duration::from_secs(300)), reply.cooldown);
+        let reply = parse_reply(&page(
+            "You gave an answer too recently. You have 30s left to wait.",
+        ));
+        assert_eq!(Some(Duration::from_secs(30)), reply.cooldown);
+        assert_eq!(
+            None,
+            parse_reply(&page("That's the right answer!")).cooldown
+        );
+    }
+
+    /// Answers a single request with a page holding `message`, and returns the request's URL,
+    /// body and session cookie.
+    fn serve_once(
+        message: &'static str,
+    ) -> (u16, thread::JoinHandle<(String, String, Option<String>)>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let handle = thread::spawn(move || {
+            let mut request = server.recv().unwrap();
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            let cookie = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("Cookie"))
+                .map(|header| header.value.to_string());
+            let url = request.url().to_string();
+            let response = tiny_http::Response::from_string(page(message));
+            request.respond(response).unwrap();
+            (url, body, cookie)
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn test_submit_to_local_server() {
+        let (port, handle) = serve_once("That's not the right answer; your answer is too high.");
+        let client = Client::new(
+            &format!("http://127.0.0.1:{port}/"),
+            Some("secret".to_string()),
+        );
+        let day = Day::new(2023, 8).unwrap();
+        let reply = client.submit(day, 2, &Answer::from(42)).unwrap();
+        assert_eq!(Response::TooHigh, reply.response);
+
+        let (url, body, cookie) = handle.join().unwrap();
+        assert_eq!("/2023/day/8/answer", url);
+        assert_eq!("level=2&answer=42", body);
+        assert_eq!(Some("session=secret".to_string()), cookie);
+    }
+
+    #[test]
+    fn test_cooldown_after_wrong_answer_is_recorded() {
+        let (port, handle) = serve_once(
+            "That's not the right answer; your answer is too low. Please wait one minute before trying again.",
+        );
+        let client = Client::new(&format!("http://127.0.0.1:{port}"), None);
+        let day = Day::new(2023, 8).unwrap();
+        let reply = client.submit(day, 1, &Answer::from(10)).unwrap();
+        handle.join().unwrap();
+
+        let directory = std::env::temp_dir().join(format!("aoc-cooldown-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let mut guesses = Guesses::load(&directory).unwrap();
+        guesses.record(1, &Answer::from(10), &reply, 1000);
+        // Any other answer still has to wait for the cooldown.
+        assert!(guesses.check(1, &Answer::from(20), 1030).is_err());
+        assert!(guesses.check(1, &Answer::from(20), 1060).is_ok());
+        assert!(guesses.check(1, &Answer::from(5), 1060).is_err());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::answer::Answer;
+use crate::day::{parse_output, Day, Solution, REAL_MODE, UNKNOWN_MODE};
+use crate::format_answer;
+use crate::store::{AnswerStore, Verdict};
+
+// Editors often write a file in several steps, so wait for changes to settle before rebuilding.
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Line-by-line differences between two answers, prefixed with `-` for the old and `+` for the
+/// new lines, and with spaces for unchanged lines of rasters.
+pub fn diff(old: &Answer, new: &Answer) -> Vec<String> {
+    let old = old.to_string();
+    let new = new.to_string();
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let mut lines = Vec::new();
+    for index in 0..old_lines.len().max(new_lines.len()) {
+        match (old_lines.get(index), new_lines.get(index)) {
+            (Some(old_line), Some(new_line)) if old_line == new_line => {
+                lines.push(format!("  {old_line}"));
+            }
+            (old_line, new_line) => {
+                if let Some(old_line) = old_line {
+                    lines.push(format!("- {old_line}"));
+                }
+                if let Some(new_line) = new_line {
+                    lines.push(format!("+ {new_line}"));
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// Prints the answers of one mode, with their verdict against the stored answers and a diff
+/// against the previous run for every answer that changed.
+fn report(
+    title: &str,
+    solutions: &[&Solution],
+    store: &AnswerStore,
+    previous: &HashMap<(String, u8), Answer>,
+) {
+    if solutions.is_empty() {
+        return;
+    }
+    println!("{title}");
+    for solution in solutions {
+        let (verdict, expected) = match store.check(solution) {
+            Verdict::Correct => ("pass", None),
+            Verdict::Wrong { expected } => ("FAIL", Some(expected)),
+            Verdict::Unknown => ("no expected answer", None),
+        };
+        println!(
+            "  {} part {} ({verdict}): {}",
+            solution.mode,
+            solution.part,
+            format_answer(&solution.answer)
+        );
+        if let Some(expected) = expected {
+            println!("    expected:");
+            for line in diff(&expected, &solution.answer) {
+                println!("    {line}");
+            }
+        }
+        let key = (solution.mode.clone(), solution.part);
+        if let Some(old) = previous.get(&key).filter(|&old| *old != solution.answer) {
+            println!("    changed since the last run:");
+            for line in diff(old, &solution.answer) {
+                println!("    {line}");
+            }
+        }
+    }
+}
+
+fn any_wrong(solutions: &[Solution], store: &AnswerStore) -> bool {
+    solutions
+        .iter()
+        .any(|solution| matches!(store.check(solution), Verdict::Wrong { .. }))
+}
+
+/// Runs the day until it announces the real input, and only lets it go on to solve that when none
+/// of the examples before it failed. Returns the output and whether the real input was skipped.
+fn run_gated(day: Day, root: &Path, store: &AnswerStore) -> Result<(String, bool), String> {
+    let mut child = day.spawn(root, &[])?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| format!("Failed to read the output of {day}."))?;
+    let mut output = String::new();
+    let mut skipped = false;
+    for line in BufReader::new(stdout).lines() {
+        let line = line.map_err(|error| format!("Failed to read the output of {day}: {error}"))?;
+        let starts_real = line
+            .strip_prefix("Mode: ")
+            .is_some_and(|mode| mode.trim() == REAL_MODE);
+        if starts_real && any_wrong(&parse_output(&output), store) {
+            skipped = true;
+            let _ = child.kill();
+            break;
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+    let status = child
+        .wait()
+        .map_err(|error| format!("Failed to run {day}: {error}"))?;
+    if !skipped && !status.success() {
+        return Err(format!("{day} failed."));
+    }
+    Ok((output, skipped))
+}
+
+/// Rebuilds and reruns the day, reporting the examples before the real input.
+fn check(
+    day: Day,
+    root: &Path,
+    previous: &mut HashMap<(String, u8), Answer>,
+) -> Result<(), String> {
+    day.build(root)?;
+    let store = AnswerStore::load(&day.directory(root))?;
+    let (output, skipped) = run_gated(day, root, &store)?;
+    let solutions = parse_output(&output);
+
+    let (real, other): (Vec<&Solution>, Vec<&Solution>) = solutions
+        .iter()
+        .partition(|solution| solution.mode == REAL_MODE);
+    let (unknown, examples): (Vec<&Solution>, Vec<&Solution>) = other
+        .into_iter()
+        .partition(|solution| solution.mode == UNKNOWN_MODE);
+    report("Examples", &examples, &store, previous);
+    if skipped {
+        println!("Real input skipped, as an example failed.");
+    }
+    report("Real input", &real, &store, previous);
+    report("Unknown input", &unknown, &store, previous);
+
+    for solution in solutions {
+        previous.insert((solution.mode, solution.part), solution.answer);
+    }
+    Ok(())
+}
+
+/// Reruns the day whenever a file in its `src/` or `data/` changes, until interrupted.
+pub fn watch(day: Day, root: &Path) -> Result<(), String> {
+    let directory = day.directory(root);
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)
+        .map_err(|error| format!("Failed to start watching: {error}"))?;
+    for folder in ["src", "data"] {
+        watcher
+            .watch(&directory.join(folder), RecursiveMode::Recursive)
+            .map_err(|error| format!("Failed to watch {folder}/ of {day}: {error}"))?;
+    }
+
+    let mut previous = HashMap::new();
+    loop {
+        println!("\n== {day} ==");
+        if let Err(error) = check(day, root, &mut previous) {
+            println!("{error}");
+        }
+        println!("Watching {} for changes.", directory.display());
+
+        // Block until something changes, then let further changes settle.
+        loop {
+            let event = receiver
+                .recv()
+                .map_err(|_| "Stopped watching.".to_string())?;
+            if event.is_ok_and(|event| !event.kind.is_access()) {
+                break;
+            }
+        }
+        while receiver.recv_timeout(SETTLE_TIME).is_ok() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_numbers() {
+        assert_eq!(
+            vec!["- 142", "+ 143"],
+            diff(&Answer::from(142), &Answer::from(143))
+        );
+    }
+
+    #[test]
+    fn test_diff_rasters() {
+        let old = Answer::from("#..#\n####\n#..#");
+        let new = Answer::from("#..#\n#.##\n#..#\n....");
+        assert_eq!(
+            vec!["  #..#", "- ####", "+ #.##", "  #..#", "+ ...."],
+            diff(&old, &new)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Keeps the `k` largest items pushed so far, using `O(k)` memory.
+struct TopK<T: Ord> {
+    k: usize,
+    // Min-heap, so the smallest kept item is the one to evict.
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if self
+            .heap
+            .peek()
+            .is_some_and(|Reverse(smallest)| item > *smallest)
+        {
+            self.heap.pop();
+            self.heap.push(Reverse(item));
+        }
+    }
+
+    /// The kept items, largest first.
+    fn into_sorted_vec(self) -> Vec<T> {
+        // Sorting `Reverse` ascending puts the largest item first.
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+/// The number of items read up to and including the first `n` distinct items in a row.
+fn first_distinct_window<T: Eq + Hash>(items: &[T], n: usize) -> Option<usize> {
+    if n == 0 {
+        return Some(0);
+    }
+    // How often every item occurs in the window; the window is distinct once it holds `n` keys.
+    let mut counts: HashMap<&T, usize> = HashMap::new();
+    for (index, item) in items.iter().enumerate() {
+        *counts.entry(item).or_insert(0) += 1;
+        if index >= n {
+            let leaving = &items[index - n];
+            if let Some(count) = counts.get_mut(leaving) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(leaving);
+                }
+            }
+        }
+        if counts.len() == n {
+            return Some(index + 1);
+        }
+    }
+    None
+}
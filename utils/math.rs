@@ -1,27 +1,15 @@
-fn solve_quadratic(a: f32, b: f32, c: f32) -> Option<(f32, f32)> {
-    let discriminant = b * b - 4.0 * a * c;
-
-    if discriminant < 0.0 {
-        // No real solutions.
-        None
-    } else {
-        let sqrt_discriminant = discriminant.sqrt();
-        let x1 = (-b + sqrt_discriminant) / (2.0 * a);
-        let x2 = (-b - sqrt_discriminant) / (2.0 * a);
-        Some((x1, x2))
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
     }
+    a
 }
 
-fn is_integer(n: f32) -> bool {
-    n.fract() == 0.0
-}
-
-fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
     }
+    a
 }
 
 fn lcm(a: u64, b: u64) -> u64 {
@@ -29,10 +17,261 @@ fn lcm(a: u64, b: u64) -> u64 {
     a / gcd(a, b) * b
 }
 
-fn lcm_of_vec(numbers: &Vec<u64>) -> u64 {
+/// Like `lcm`, but returns `None` instead of overflowing.
+fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+fn lcm_u128(a: u128, b: u128) -> u128 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    a / gcd_u128(a, b) * b
+}
+
+fn lcm_of_vec(numbers: &[u64]) -> u64 {
+    numbers.iter().copied().reduce(lcm).unwrap_or(1)
+}
+
+fn checked_lcm_of_vec(numbers: &[u64]) -> Option<u64> {
     numbers
         .iter()
-        .copied()
-        .reduce(|a, b| lcm(a, b))
-        .unwrap_or(1)
+        .try_fold(1, |result, &number| checked_lcm(result, number))
+}
+
+/// Largest integer whose square does not exceed `n`, using Newton's method on integers.
+fn integer_sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Start above the root, so every iteration decreases monotonically towards it.
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// Number of integers `t` in `0..=time` with `t * (time - t) > distance`, computed exactly.
+fn count_exceeding_products(time: u64, distance: u64) -> u64 {
+    let (time, distance) = (time as u128, distance as u128);
+    let product = |t: u128| t * (time - t);
+
+    // The product peaks in the middle, so without a solution there, there is none at all.
+    let middle = time / 2;
+    if product(middle) <= distance {
+        return 0;
+    }
+
+    // Start from the lower root of t² - time * t + distance and correct the rounding.
+    let root = integer_sqrt(time * time - 4 * distance);
+    let mut low = (time - root) / 2;
+    while product(low) <= distance {
+        low += 1;
+    }
+    while low > 0 && product(low - 1) > distance {
+        low -= 1;
+    }
+
+    // Solutions are symmetric around the middle.
+    let high = time - low;
+    (high - low + 1) as u64
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y = g = gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, if `a` and `modulus` are coprime.
+fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a as i128, modulus as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus as i128) as u64)
+}
+
+/// `base ^ exponent mod modulus` by repeated squaring, without intermediate overflow.
+fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "Modulus should be positive.");
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// Solves the system `t ≡ residue (mod modulus)` for all given pairs, where moduli need not be
+/// coprime. Returns the smallest non-negative solution and the lcm of the moduli, or `None` when
+/// the congruences contradict each other or the lcm does not fit in a `u128`.
+fn chinese_remainder_u128(congruences: &[(u128, u128)]) -> Option<(u128, u128)> {
+    let (mut residue, mut modulus): (u128, u128) = (0, 1);
+    for &(other_residue, other_modulus) in congruences {
+        if other_modulus == 0 {
+            return None;
+        }
+        let other_residue = other_residue % other_modulus;
+        let g = gcd_u128(modulus, other_modulus);
+        // The difference between both residues must be a multiple of the gcd.
+        let (difference, negative) = if other_residue >= residue {
+            (other_residue - residue, false)
+        } else {
+            (residue - other_residue, true)
+        };
+        if difference % g != 0 {
+            return None;
+        }
+
+        // Solve (modulus / g) * steps ≡ difference / g (mod other_modulus / g).
+        let step_modulus = other_modulus / g;
+        let mut target = (difference / g) % step_modulus;
+        if negative {
+            target = (step_modulus - target) % step_modulus;
+        }
+        let inverse = mod_inverse_u128(modulus / g % step_modulus, step_modulus)?;
+        let steps = mul_mod_u128(target, inverse, step_modulus);
+
+        // Step from the current solution in multiples of the current modulus.
+        let new_modulus = modulus.checked_mul(step_modulus)?;
+        residue = (residue + modulus * steps) % new_modulus;
+        modulus = new_modulus;
+    }
+    Some((residue, modulus))
+}
+
+/// Like `chinese_remainder_u128`, but also returns `None` when the lcm does not fit in a `u64`.
+fn chinese_remainder(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let congruences: Vec<(u128, u128)> = congruences
+        .iter()
+        .map(|&(residue, modulus)| (residue as u128, modulus as u128))
+        .collect();
+    let (residue, modulus) = chinese_remainder_u128(&congruences)?;
+    Some((residue as u64, u64::try_from(modulus).ok()?))
+}
+
+/// `a * b mod modulus` without overflowing, by doubling.
+fn mul_mod_u128(mut a: u128, mut b: u128, modulus: u128) -> u128 {
+    let mut result = 0;
+    a %= modulus;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_u128(result, a, modulus);
+        }
+        a = add_mod_u128(a, a, modulus);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_mod_u128(a: u128, b: u128, modulus: u128) -> u128 {
+    // Both operands are below the modulus, so subtracting first cannot underflow.
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+/// Modular inverse on `u128`, using the extended Euclidean algorithm on residues only.
+fn mod_inverse_u128(a: u128, modulus: u128) -> Option<u128> {
+    if modulus == 1 {
+        return Some(0);
+    }
+    // Track the Bézout coefficient of `a` modulo `modulus`, so it never goes negative.
+    let (mut old_r, mut r) = (a % modulus, modulus);
+    let (mut old_x, mut x) = (1u128, 0u128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        let product = mul_mod_u128(quotient, x, modulus);
+        (old_x, x) = (
+            x,
+            add_mod_u128(old_x, (modulus - product) % modulus, modulus),
+        );
+    }
+    (old_r == 1).then_some(old_x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_gcd_and_inverse() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(2, g);
+        assert_eq!(2, 240 * x + 46 * y);
+        assert_eq!(Some(5), mod_inverse(3, 7));
+        // 6 and 9 share a factor 3, so there is no inverse.
+        assert_eq!(None, mod_inverse(6, 9));
+        assert_eq!(None, mod_inverse_u128(6, 9));
+        assert_eq!(None, mod_inverse(3, 0));
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(445, mod_pow(4, 13, 497));
+        assert_eq!(0, mod_pow(5, 0, 1));
+        // The intermediate squares exceed a u64.
+        assert_eq!(1, mod_pow(u64::MAX - 1, 2, u64::MAX));
+    }
+
+    #[test]
+    fn test_chinese_remainder_non_coprime() {
+        // t ≡ 2 (mod 4) and t ≡ 4 (mod 6) have period lcm(4, 6) = 12.
+        assert_eq!(Some((10, 12)), chinese_remainder(&[(2, 4), (4, 6)]));
+        // An odd and an even residue modulo even numbers contradict each other.
+        assert_eq!(None, chinese_remainder(&[(1, 4), (2, 6)]));
+        assert_eq!(None, chinese_remainder(&[(0, 0)]));
+    }
+
+    #[test]
+    fn test_u128_near_the_limit() {
+        // u128::MAX - 1 ≡ -1, so its square is 1.
+        assert_eq!(1, mul_mod_u128(u128::MAX - 1, u128::MAX - 1, u128::MAX));
+        assert_eq!(
+            u128::MAX - 4,
+            add_mod_u128(u128::MAX - 2, u128::MAX - 2, u128::MAX)
+        );
+        assert_eq!(
+            Some(u128::MAX - 1),
+            mod_inverse_u128(u128::MAX - 1, u128::MAX)
+        );
+        // Coprime moduli whose product just fits, and one more that does not.
+        let big = 1u128 << 64;
+        let (residue, modulus) = chinese_remainder_u128(&[(1, big - 1), (2, big)]).unwrap();
+        assert_eq!((big - 1) * big, modulus);
+        assert_eq!((1, 2), (residue % (big - 1), residue % big));
+        assert_eq!(
+            None,
+            chinese_remainder_u128(&[(1, big - 1), (2, big), (0, 3)])
+        );
+        // The solution is right, but its period does not fit in a u64.
+        assert_eq!(None, chinese_remainder(&[(1, u64::MAX), (2, u64::MAX - 1)]));
+    }
 }
@@ -1,18 +1,7 @@
-use std::fmt::Display;
-
 fn to_2d_vector(input: &str) -> Vec<Vec<char>> {
     input.lines().map(|row| row.chars().collect()).collect()
 }
 
-fn print_2d_matrix<T: Display>(matrix: &Vec<Vec<T>>) {
-    for row in matrix {
-        for cell in row {
-            print!("{} ", cell);
-        }
-        println!();
-    }
-}
-
 fn check_adjacent_cells(matrix: &Vec<Vec<bool>>, row_index: usize, column_index: usize) -> bool {
     /// Checks adjacent cells of a specified cell in a boolean matrix for a `true` value.
     ///
@@ -43,3 +32,149 @@ fn check_adjacent_cells(matrix: &Vec<Vec<bool>>, row_index: usize, column_index:
 
     false
 }
+
+/// A rectangular grid stored row-major in a single vector, indexed by `(row, column)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "Grid dimensions do not match cells."
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Parses one row per line, converting every character with `f`.
+    fn parse(input: &str, f: impl Fn(char) -> T) -> Self {
+        let mut width = 0;
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let row_start = cells.len();
+            cells.extend(line.chars().map(&f));
+            width = cells.len() - row_start;
+            height += 1;
+        }
+        Grid::new(width, height, cells)
+    }
+
+    fn get(&self, row: usize, column: usize) -> Option<&T> {
+        if row < self.height && column < self.width {
+            Some(&self.cells[row * self.width + column])
+        } else {
+            None
+        }
+    }
+
+    fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Iterates over all positions in row-major order.
+    fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)))
+    }
+}
+
+impl<T> std::ops::Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        assert!(
+            row < self.height && column < self.width,
+            "Position out of bounds."
+        );
+        &self.cells[row * self.width + column]
+    }
+}
+
+impl<T> std::ops::IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.height && column < self.width,
+            "Position out of bounds."
+        );
+        &mut self.cells[row * self.width + column]
+    }
+}
+
+/// A position in a `Grid`, counted from the top-left corner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Point {
+    row: usize,
+    column: usize,
+}
+
+impl Point {
+    const fn new(row: usize, column: usize) -> Self {
+        Point { row, column }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Returns the position of the first cell matching `predicate`, in row-major order.
+    fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        let index = self.cells.iter().position(predicate)?;
+        Some(Point::new(index / self.width, index % self.width))
+    }
+
+    /// Returns the up to four orthogonal neighbours of `point` that lie within the grid.
+    fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> {
+        let (height, width) = (self.height, self.width);
+        [
+            point
+                .row
+                .checked_sub(1)
+                .map(|row| Point::new(row, point.column)),
+            Some(Point::new(point.row + 1, point.column)).filter(|p| p.row < height),
+            point
+                .column
+                .checked_sub(1)
+                .map(|column| Point::new(point.row, column)),
+            Some(Point::new(point.row, point.column + 1)).filter(|p| p.column < width),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl<T> std::ops::Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        &self[(point.row, point.column)]
+    }
+}
+
+impl<T> std::ops::IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        &mut self[(point.row, point.column)]
+    }
+}
@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+/// An inclusive range of section ids, `start..=end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Section {
+    start: u32,
+    end: u32,
+}
+
+impl Section {
+    fn new(start: u32, end: u32) -> Result<Section, String> {
+        if start > end {
+            return Err(format!(
+                "Section should not end before it starts: {start}-{end}"
+            ));
+        }
+        Ok(Section { start, end })
+    }
+
+    /// Whether `other` lies entirely within this section.
+    fn contains(&self, other: &Section) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    fn overlaps(&self, other: &Section) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    fn intersection(&self, other: &Section) -> Option<Section> {
+        self.overlaps(other).then(|| Section {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    fn len(&self) -> u64 {
+        // As a u64, so the full u32 range does not overflow.
+        (self.end - self.start) as u64 + 1
+    }
+}
+
+impl FromStr for Section {
+    type Err = String;
+
+    /// Parses a section such as `2-4`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| format!("Missing dash in section: {s}"))?;
+        let parse = |bound: &str| {
+            bound
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid section bound: {bound}"))
+        };
+        Section::new(parse(start)?, parse(end)?)
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+
 fn reverse_string(string: &str) -> String {
     string.chars().rev().collect()
 }
@@ -5,3 +8,318 @@ fn reverse_string(string: &str) -> String {
 fn remove_whitespace(string: &str) -> String {
     string.chars().filter(|c| !c.is_whitespace()).collect()
 }
+
+/// Groups of consecutive non-blank lines, separated by one or more blank lines. The last group
+/// does not need a trailing blank line.
+struct Paragraphs<'a> {
+    lines: std::str::Lines<'a>,
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut paragraph = Vec::new();
+        for line in self.lines.by_ref() {
+            if !line.trim().is_empty() {
+                paragraph.push(line);
+            } else if !paragraph.is_empty() {
+                break;
+            }
+        }
+        (!paragraph.is_empty()).then_some(paragraph)
+    }
+}
+
+fn paragraphs(input: &str) -> Paragraphs<'_> {
+    Paragraphs {
+        lines: input.lines(),
+    }
+}
+
+/// A match of a vocabulary pattern, as a byte range into the scanned string.
+#[derive(Debug, PartialEq, Eq)]
+struct Match<'a, T> {
+    start: usize,
+    end: usize,
+    value: &'a T,
+}
+
+/// An Aho–Corasick automaton over the bytes of a set of patterns.
+struct Automaton {
+    transitions: Vec<HashMap<u8, usize>>,
+    failure: Vec<usize>,
+    // Indices of all patterns ending in each state, including those reached via failure links.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new(patterns: &[Vec<u8>]) -> Self {
+        let mut automaton = Automaton {
+            transitions: vec![HashMap::new()],
+            failure: vec![0],
+            outputs: vec![Vec::new()],
+        };
+
+        // Build the trie.
+        for (index, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for &byte in pattern {
+                state = match automaton.transitions[state].get(&byte) {
+                    Some(&next) => next,
+                    None => {
+                        let next = automaton.transitions.len();
+                        automaton.transitions.push(HashMap::new());
+                        automaton.failure.push(0);
+                        automaton.outputs.push(Vec::new());
+                        automaton.transitions[state].insert(byte, next);
+                        next
+                    }
+                };
+            }
+            automaton.outputs[state].push(index);
+        }
+
+        // Compute failure links breadth-first, so shorter suffixes are always known.
+        let mut queue: VecDeque<usize> = automaton.transitions[0].values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let children: Vec<(u8, usize)> = automaton.transitions[state]
+                .iter()
+                .map(|(&byte, &child)| (byte, child))
+                .collect();
+            for (byte, child) in children {
+                let fallback = automaton.next_state(automaton.failure[state], byte);
+                automaton.failure[child] = fallback;
+                let inherited = automaton.outputs[fallback].clone();
+                automaton.outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        automaton
+    }
+
+    fn next_state(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.transitions[state].get(&byte) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.failure[state];
+        }
+    }
+}
+
+/// Finds every, possibly overlapping, occurrence of a configurable vocabulary of tokens.
+/// Scanning works from both ends, so the first and last token of a line are found without
+/// reversing or rewriting it.
+struct Scanner<T> {
+    vocabulary: Vec<(String, T)>,
+    forward: Automaton,
+    backward: Automaton,
+    max_length: usize,
+}
+
+impl<T> Scanner<T> {
+    fn new<S: Into<String>>(vocabulary: impl IntoIterator<Item = (S, T)>) -> Self {
+        let vocabulary: Vec<(String, T)> = vocabulary
+            .into_iter()
+            .map(|(pattern, value)| (pattern.into(), value))
+            .filter(|(pattern, _)| !pattern.is_empty())
+            .collect();
+        let patterns: Vec<Vec<u8>> = vocabulary
+            .iter()
+            .map(|(pattern, _)| pattern.bytes().collect())
+            .collect();
+        let reversed_patterns: Vec<Vec<u8>> = vocabulary
+            .iter()
+            .map(|(pattern, _)| pattern.bytes().rev().collect())
+            .collect();
+        Scanner {
+            max_length: patterns.iter().map(Vec::len).max().unwrap_or(0),
+            forward: Automaton::new(&patterns),
+            backward: Automaton::new(&reversed_patterns),
+            vocabulary,
+        }
+    }
+
+    fn to_match(&self, index: usize, start: usize) -> Match<'_, T> {
+        let (pattern, value) = &self.vocabulary[index];
+        Match {
+            start,
+            end: start + pattern.len(),
+            value,
+        }
+    }
+
+    /// Yields all matches from left to right, ordered by their end.
+    fn find_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match<'a, T>> + 'a {
+        let mut state = 0;
+        haystack
+            .bytes()
+            .enumerate()
+            .flat_map(move |(position, byte)| {
+                state = self.forward.next_state(state, byte);
+                self.forward.outputs[state].iter().map(move |&index| {
+                    self.to_match(index, position + 1 - self.vocabulary[index].0.len())
+                })
+            })
+    }
+
+    /// Yields all matches from right to left, ordered by their start.
+    fn rfind_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match<'a, T>> + 'a {
+        let mut state = 0;
+        haystack
+            .bytes()
+            .enumerate()
+            .rev()
+            .flat_map(move |(position, byte)| {
+                state = self.backward.next_state(state, byte);
+                self.backward.outputs[state]
+                    .iter()
+                    .map(move |&index| self.to_match(index, position))
+            })
+    }
+
+    /// Returns the match that starts first.
+    fn first<'a>(&'a self, haystack: &'a str) -> Option<Match<'a, T>> {
+        let mut best: Option<Match<T>> = None;
+        for candidate in self.find_iter(haystack) {
+            if let Some(best) = &best {
+                // Later matches end later, so they can no longer start before the best one.
+                if candidate.end >= best.start + self.max_length {
+                    break;
+                }
+            }
+            if best
+                .as_ref()
+                .is_none_or(|best| candidate.start < best.start)
+            {
+                best = Some(candidate);
+            }
+        }
+        best
+    }
+
+    /// Returns the match that starts last.
+    fn last<'a>(&'a self, haystack: &'a str) -> Option<Match<'a, T>> {
+        self.rfind_iter(haystack).next()
+    }
+}
+
+/// The vocabulary of the digits 1 to 9, optionally including their spelled-out names.
+fn digit_vocabulary(spelled: bool) -> Vec<(&'static str, u32)> {
+    const DIGITS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut vocabulary: Vec<(&'static str, u32)> = DIGITS.into_iter().zip(1..).collect();
+    if spelled {
+        vocabulary.extend(WORDS.into_iter().zip(1..));
+    }
+    vocabulary
+}
+
+/// A set of characters as a 64-bit mask. Letters take bits `1..=52` in the order `a-z`, `A-Z`, so
+/// the bit index of a letter is its priority; digits extend the set with bits `53..=62`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+struct CharSet(u64);
+
+impl CharSet {
+    const EMPTY: CharSet = CharSet(0);
+    // Number of distinct characters a set can hold.
+    const CAPACITY: usize = 62;
+
+    fn bit(character: char) -> Option<u32> {
+        match character {
+            'a'..='z' => Some(character as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(character as u32 - 'A' as u32 + 27),
+            '0'..='9' => Some(character as u32 - '0' as u32 + 53),
+            _ => None,
+        }
+    }
+
+    /// The priority of a letter: `a-z` are 1 to 26 and `A-Z` are 27 to 52.
+    fn priority(character: char) -> Option<u32> {
+        CharSet::bit(character).filter(|&bit| bit <= 52)
+    }
+
+    fn insert(&mut self, character: char) -> Result<(), String> {
+        let bit =
+            CharSet::bit(character).ok_or_else(|| format!("Unsupported character: {character}"))?;
+        self.0 |= 1 << bit;
+        Ok(())
+    }
+
+    /// Adds the character if it is absent and removes it if it is present.
+    fn toggle(&mut self, character: char) -> Result<(), String> {
+        let bit =
+            CharSet::bit(character).ok_or_else(|| format!("Unsupported character: {character}"))?;
+        self.0 ^= 1 << bit;
+        Ok(())
+    }
+
+    fn contains(&self, character: char) -> bool {
+        CharSet::bit(character).is_some_and(|bit| self.0 & 1 << bit != 0)
+    }
+
+    fn intersection(&self, other: &CharSet) -> CharSet {
+        CharSet(self.0 & other.0)
+    }
+
+    fn union(&self, other: &CharSet) -> CharSet {
+        CharSet(self.0 | other.0)
+    }
+
+    fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The sum of the priorities of all letters in the set.
+    fn priority_sum(&self) -> u32 {
+        (1..=52).filter(|bit| self.0 & 1 << bit != 0).sum()
+    }
+}
+
+impl FromStr for CharSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = CharSet::EMPTY;
+        for character in s.chars() {
+            set.insert(character)?;
+        }
+        Ok(set)
+    }
+}
+
+/// Like `first_distinct_window`, but in constant time per character using a `CharSet`. Fails for
+/// characters the set cannot hold and windows larger than `CharSet::CAPACITY`.
+fn first_distinct_chars(characters: &[char], n: usize) -> Result<Option<usize>, String> {
+    if n > CharSet::CAPACITY {
+        return Err(format!("Window too large for a character set: {n}"));
+    }
+    if n == 0 {
+        return Ok(Some(0));
+    }
+    // Toggling every character entering and leaving the window keeps the characters that occur
+    // an odd number of times. Duplicates cancel out, so only a window of `n` distinct characters
+    // holds `n` of them.
+    let mut window = CharSet::EMPTY;
+    for (index, &character) in characters.iter().enumerate() {
+        window.toggle(character)?;
+        if index >= n {
+            window.toggle(characters[index - n])?;
+        }
+        if window.len() as usize == n {
+            return Ok(Some(index + 1));
+        }
+    }
+    Ok(None)
+}